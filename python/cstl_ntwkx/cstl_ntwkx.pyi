@@ -1,5 +1,6 @@
-from ast import Dict, Tuple
+from ast import Dict, List, Tuple
 from enum import Enum
+from typing import Optional, Union
 
 
 class ConstellationType(Enum):
@@ -7,6 +8,60 @@ class ConstellationType(Enum):
     Delta = 2
 
 
+class ReferenceFrame(Enum):
+    # EME2000 (J2000)
+    ECI = 1
+    # IAU rotation model of the earth, approximates ITRF without nutation and polar motion
+    ECEF = 2
+
+
+class LinkBudget:
+    def __init__(
+        self,
+        transmit_power: float,
+        transmit_gain: float,
+        receive_gain: float,
+        frequency: float,
+        bandwidth: float,
+        noise_temperature: float,
+        zenith_atmospheric_loss: float = 0.0,
+        other_losses: float = 0.0,
+        modcods: Optional[List[Tuple[float, float]]] = None,
+    ):
+        pass
+
+
+class EventStream:
+    def __iter__(self) -> "EventStream":
+        pass
+
+    def __next__(self) -> Dict[str, object]:
+        pass
+
+    def __len__(self) -> int:
+        pass
+
+    def write(self, path: str, append: bool = False):
+        pass
+
+
+class LoadReport:
+    def table(self) -> Dict[str, List]:
+        pass
+
+    def top_bottlenecks(self, top: int = 10) -> Dict[str, List]:
+        pass
+
+    def load_distributions(self) -> List[Dict[str, object]]:
+        pass
+
+    def unrouted(self) -> List[Tuple[float, float]]:
+        pass
+
+    def write(self, path: str):
+        pass
+
+
 class Constellation:
     def add_groundstation(
         self,
@@ -14,12 +69,149 @@ class Constellation:
         lat: float,
         lon: float,
         alt: float,
+        weight: float = 1.0,
+        gateway: bool = False,
     ):
         pass
 
+    def add_terminal(
+        self,
+        name: str,
+        waypoints: List[Tuple[float, float, float, int]],
+        weight: float = 1.0,
+        gateway: bool = False,
+    ) -> int:
+        pass
+
+    def add_terminal_route(
+        self,
+        name: str,
+        start: Tuple[float, float],
+        end: Tuple[float, float],
+        alt: float,
+        speed: float,
+        departure: int = 0,
+        weight: float = 1.0,
+        gateway: bool = False,
+    ) -> int:
+        pass
+
+    def add_geo_relay(self, lon: float, terminals: int = 4) -> int:
+        pass
+
+    def add_relay(
+        self,
+        sma: float,
+        ecc: float,
+        inc: float,
+        raan: float,
+        aop: float,
+        ta: float,
+        terminals: int = 4,
+    ) -> int:
+        pass
+
+    def set_relay_link_rules(
+        self,
+        max_range: Optional[float] = None,
+        grazing_altitude: float = 100.0,
+        satellite_terminals: int = 1,
+    ):
+        pass
+
+    def remove_groundstation(self, groundstation: Union[int, str]):
+        pass
+
+    def move_groundstation(
+        self, groundstation: Union[int, str], lat: float, lon: float, alt: float
+    ):
+        pass
+
+    def add_satellite(self, plane: int, slot: int) -> int:
+        pass
+
+    def insert_satellite(
+        self,
+        plane: int,
+        aol: float,
+        altitude: Optional[float] = None,
+        slot: Optional[int] = None,
+    ) -> int:
+        pass
+
+    def remove_satellite(self, id: int):
+        pass
+
+    def replace_satellite(self, id: int) -> int:
+        pass
+
     def propagate(self, step: int):
         pass
 
+    def propagate_events(self, step: int, threshold: float = 1.0) -> EventStream:
+        pass
+
+    def set_carrier_frequency(self, frequency: Optional[float]):
+        pass
+
+    def set_isl_link_budget(self, budget: Optional[LinkBudget]):
+        pass
+
+    def set_gsl_link_budget(self, budget: Optional[LinkBudget]):
+        pass
+
+    def set_rain_fade_margin(self, fade_margin: Optional[float], remove: bool = True):
+        pass
+
+    def set_rain_rate(self, groundstation: str, rain_rate: float):
+        pass
+
+    def set_rain_zone(self, groundstation: str, zone: str):
+        pass
+
+    def load_rain_time_series(self, path: str):
+        pass
+
+    def fail_satellite(
+        self, id: int, start: Optional[int] = None, end: Optional[int] = None
+    ):
+        pass
+
+    def fail_groundstation(
+        self, groundstation: str, start: Optional[int] = None, end: Optional[int] = None
+    ):
+        pass
+
+    def fail_plane(
+        self, plane: int, start: Optional[int] = None, end: Optional[int] = None
+    ):
+        pass
+
+    def fail_isl(
+        self,
+        first: int,
+        second: int,
+        start: Optional[int] = None,
+        end: Optional[int] = None,
+    ):
+        pass
+
+    def inject_random_failures(
+        self,
+        rate: float,
+        duration: int,
+        seed: int = 0,
+        repair_time: Optional[int] = None,
+        isls: bool = False,
+    ):
+        pass
+
+    def clear_failures(self):
+        pass
+
+    def get_failures(self) -> List[Dict[str, object]]:
+        pass
+
 
 def create_constellation(
     satellites: int,
@@ -28,7 +220,9 @@ def create_constellation(
     altitude: int,
     inclination: float,
     mim_elevation: float,
-    constellation_type: ConstellationType
+    constellation_type: ConstellationType,
+    eccentricity: float = 0.0,
+    argument_of_perigee: float = 0.0,
 ) -> Constellation:
     pass
 
@@ -39,7 +233,8 @@ def extract_graph(constellation: Constellation) -> str:
 
 def extract_positions_3d(
     constellation: Constellation,
-) -> Dict[int, Tuple[float, float, float]]:
+    frame: ReferenceFrame = ReferenceFrame.ECI,
+) -> Dict[int, Tuple[str, Tuple[float, float, float], str]]:
     pass
 
 
@@ -47,3 +242,153 @@ def project_3d_positions(
     constellation: Constellation,
 ) -> Dict[int, Tuple[float, float, float]]:
     pass
+
+
+def extract_temporal_graph(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+):
+    pass
+
+
+def write_temporal_graph(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+    path: str,
+):
+    pass
+
+
+def extract_contacts(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+    rate: float = 1e6,
+) -> List[Dict[str, float]]:
+    pass
+
+
+def write_contact_plan(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+    path: str,
+    rate: float = 1e6,
+):
+    pass
+
+
+def find_route(
+    constellation: Constellation,
+    source: int,
+    destination: int,
+    start: int,
+    size: float,
+    duration: int,
+    step: int,
+    rate: float = 1e6,
+) -> Optional[Dict[str, object]]:
+    pass
+
+
+def simulate(
+    constellation: Constellation,
+    flows: List[Tuple[int, int, float, float]],
+    duration: int,
+    topology_step: int,
+    routing_interval: Optional[int] = None,
+    static_routes: Optional[Dict[Tuple[int, int], int]] = None,
+    queue_size: int = 100,
+    rate: float = 100.0,
+    seed: int = 0,
+    poisson: bool = False,
+) -> List[Dict[str, object]]:
+    pass
+
+
+def extract_traffic_matrix(
+    constellation: Constellation,
+    model: str = "gravity",
+    total: float = 1000.0,
+    distance_exponent: float = 1.0,
+) -> List[Tuple[int, int, float]]:
+    pass
+
+
+def max_flow(
+    constellation: Constellation,
+    source: int,
+    destination: int,
+    rate: float = 100.0,
+) -> Dict[str, object]:
+    pass
+
+
+def multi_commodity_flow(
+    constellation: Constellation,
+    demands: List[Tuple[int, int, float]],
+    rate: float = 100.0,
+    epsilon: float = 0.1,
+) -> Dict[str, object]:
+    pass
+
+
+def route_traffic(
+    constellation: Constellation,
+    demands: List[Tuple[int, int, float]],
+    duration: int,
+    step: int,
+) -> LoadReport:
+    pass
+
+
+def extract_link_diff(
+    constellation: Constellation,
+) -> Dict[str, List[Tuple[int, int]]]:
+    pass
+
+
+def extract_visibility_windows(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+) -> List[Dict[str, float]]:
+    pass
+
+
+def extract_gsl_look_angles(
+    constellation: Constellation,
+) -> List[Dict[str, float]]:
+    pass
+
+
+def extract_ground_tracks(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+    plane: Optional[int] = None,
+) -> Dict[int, List[List[Tuple[float, float, float, float]]]]:
+    pass
+
+
+def analyze_coverage(
+    constellation: Constellation,
+    latitudes: List[float],
+    longitudes: List[float],
+    duration: int,
+    step: int,
+    min_elevation: Optional[float] = None,
+    grid: bool = True,
+) -> Dict[str, List]:
+    pass
+
+
+def analyze_robustness(
+    constellation: Constellation,
+    duration: int,
+    step: int,
+    top: int = 10,
+) -> List[Dict[str, object]]:
+    pass
@@ -1,2 +1,29 @@
 def test():
-    print("TEST bestanden")
\ No newline at end of file
+    print("TEST bestanden")
+
+
+def plot_ground_tracks(tracks, ax=None, **kwargs):
+    """Draws ground tracks as returned by `extract_ground_tracks` on a lon/lat map.
+
+    Each segment is drawn as its own line, so antimeridian crossings do not wrap around the map.
+    A given `color` is used for all satellites, otherwise each satellite gets the next color of the cycle.
+    Further keyword arguments are passed to `Axes.plot`.
+    """
+    import matplotlib.pyplot as plt
+
+    if ax is None:
+        _, ax = plt.subplots()
+    initial_color = kwargs.pop("color", kwargs.pop("c", None))
+    for sat, segments in tracks.items():
+        color = initial_color
+        for segment in segments:
+            lats = [lat for (_, lat, _, _) in segment]
+            lons = [lon for (_, _, lon, _) in segment]
+            (line,) = ax.plot(lons, lats, color=color, **kwargs)
+            # draw all segments of a satellite in the same color
+            color = line.get_color()
+    ax.set_xlim(-180.0, 180.0)
+    ax.set_ylim(-90.0, 90.0)
+    ax.set_xlabel("Longitude (°)")
+    ax.set_ylabel("Latitude (°)")
+    return ax
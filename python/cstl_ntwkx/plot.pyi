@@ -1,2 +1,13 @@
+from typing import Any, Dict, List, Tuple
+
+
 def test() -> any:
-    pass
\ No newline at end of file
+    pass
+
+
+def plot_ground_tracks(
+    tracks: Dict[int, List[List[Tuple[float, float, float, float]]]],
+    ax: Any = None,
+    **kwargs: Any,
+) -> Any:
+    pass
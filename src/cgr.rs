@@ -0,0 +1,215 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use uom::si::f64::{Information, Time};
+
+use crate::{constellation::node::NodeId, contact_plan::Contact};
+
+/// A contact used by a route.
+#[derive(Debug, Clone, Copy)]
+pub struct Hop {
+    contact: Contact,
+    /// Offset at which the transmission of the bundle starts
+    transmission_start: Time,
+    /// Offset at which the bundle is completely received by the next node
+    arrival: Time,
+    /// Share of the contact volume consumed by the bundle
+    consumption: f64,
+}
+
+impl Hop {
+    pub fn get_contact(&self) -> Contact {
+        self.contact
+    }
+
+    pub fn get_transmission_start(&self) -> Time {
+        self.transmission_start
+    }
+
+    pub fn get_arrival(&self) -> Time {
+        self.arrival
+    }
+
+    pub fn get_consumption(&self) -> f64 {
+        self.consumption
+    }
+}
+
+/// Best route of a bundle found by contact graph routing.
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Offset at which the bundle is available at the source
+    start: Time,
+    /// Contacts from the source to the destination, empty if both are the same node
+    hops: Vec<Hop>,
+}
+
+impl Route {
+    pub fn get_hops(&self) -> &[Hop] {
+        &self.hops
+    }
+
+    /// Returns the offset at which the bundle is delivered to the destination.
+    pub fn get_delivery_time(&self) -> Time {
+        self.hops.last().map_or(self.start, |hop| hop.arrival)
+    }
+}
+
+/// Arrival at the receiving node of a contact, ordered by the earliest arrival first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Arrival {
+    time: Time,
+    contact: usize,
+}
+
+impl Eq for Arrival {}
+
+impl Ord for Arrival {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .value
+            .total_cmp(&self.time.value)
+            .then(other.contact.cmp(&self.contact))
+    }
+}
+
+impl PartialOrd for Arrival {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Computes the transmission of a bundle of the given size over the contact,
+/// if the bundle is available at the sending node at the given time.
+/// Returns `None` if the bundle does not fit into the remaining contact volume or the contact has no rate.
+fn transmit(contact: &Contact, available: Time, size: Information) -> Option<Hop> {
+    if contact.get_rate().value <= 0.0 {
+        return None;
+    }
+    let transmission_start = if available > contact.get_start() {
+        available
+    } else {
+        contact.get_start()
+    };
+    let transmission_end = transmission_start + size / contact.get_rate();
+    if transmission_end > contact.get_end() {
+        return None;
+    }
+    let volume = (contact.get_end() - contact.get_start()) * contact.get_rate();
+    Some(Hop {
+        contact: *contact,
+        transmission_start,
+        arrival: transmission_end + contact.get_owlt(),
+        consumption: (size / volume).value,
+    })
+}
+
+/// Searches the route with the earliest delivery time of a bundle with the given size
+/// from the source to the destination, if the bundle is available at the source at the given time.
+///
+/// The contacts are the vertices of a Dijkstra search, whose distance is the arrival time at the receiving node.
+/// A bundle is transmitted over a contact as soon as both the bundle and the contact are available,
+/// and only if the transmission completes before the end of the contact.
+/// Queueing behind other bundles is not considered, each contact offers its full volume.
+/// A bundle whose source is its destination is delivered immediately without hops.
+pub(crate) fn best_route(
+    contacts: &[Contact],
+    source: NodeId,
+    destination: NodeId,
+    start: Time,
+    size: Information,
+) -> Option<Route> {
+    if source == destination {
+        return Some(Route {
+            start,
+            hops: vec![],
+        });
+    }
+    let mut search = Search::new(contacts, size, start);
+    search.relax(source, start, None);
+    while let Some(Arrival { time, contact }) = search.queue.pop() {
+        if search.visited[contact] {
+            continue;
+        }
+        search.visited[contact] = true;
+        let to = contacts[contact].get_to();
+        if to == destination {
+            return Some(search.route(contact));
+        }
+        if to != source {
+            search.relax(to, time, Some(contact));
+        }
+    }
+    None
+}
+
+/// State of the Dijkstra search over the contacts.
+struct Search<'a> {
+    contacts: &'a [Contact],
+    size: Information,
+    start: Time,
+    /// Contacts of each sending node
+    outgoing: HashMap<NodeId, Vec<usize>>,
+    /// Best transmission found so far over each contact
+    hops: Vec<Option<Hop>>,
+    predecessors: Vec<Option<usize>>,
+    visited: Vec<bool>,
+    queue: BinaryHeap<Arrival>,
+}
+
+impl<'a> Search<'a> {
+    fn new(contacts: &'a [Contact], size: Information, start: Time) -> Self {
+        let mut outgoing: HashMap<NodeId, Vec<usize>> = HashMap::new();
+        for (i, contact) in contacts.iter().enumerate() {
+            outgoing.entry(contact.get_from()).or_default().push(i);
+        }
+        Search {
+            contacts,
+            size,
+            start,
+            outgoing,
+            hops: vec![None; contacts.len()],
+            predecessors: vec![None; contacts.len()],
+            visited: vec![false; contacts.len()],
+            queue: BinaryHeap::new(),
+        }
+    }
+
+    /// Updates the arrivals of all contacts of the given node with a bundle available at the given time.
+    fn relax(&mut self, from: NodeId, available: Time, predecessor: Option<usize>) {
+        for &i in self.outgoing.get(&from).into_iter().flatten() {
+            if self.visited[i] {
+                continue;
+            }
+            let Some(hop) = transmit(&self.contacts[i], available, self.size) else {
+                continue;
+            };
+            if self.hops[i].is_none_or(|best| hop.arrival < best.arrival) {
+                self.hops[i] = Some(hop);
+                self.predecessors[i] = predecessor;
+                self.queue.push(Arrival {
+                    time: hop.arrival,
+                    contact: i,
+                });
+            }
+        }
+    }
+
+    /// Collects the hops from the source to the given final contact.
+    fn route(&self, last: usize) -> Route {
+        let mut hops = vec![self.hops[last].unwrap()];
+        let mut current = last;
+        while let Some(previous) = self.predecessors[current] {
+            hops.push(self.hops[previous].unwrap());
+            current = previous;
+        }
+        hops.reverse();
+        Route {
+            start: self.start,
+            hops,
+        }
+    }
+}
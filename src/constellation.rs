@@ -1,19 +1,44 @@
+use crate::contact_plan::{self, Contact};
+use crate::coverage::{self, CoverageStatistics};
+use crate::events::{EventStream, TopologyEvent, TopologyTracker};
+use crate::failures::{self, Failure, FailureModel, FailureTarget};
+use crate::flow::{self, MaxFlow, MultiCommodityFlow, Network};
+use crate::ground_track::{self, GroundTrack};
 use crate::groundstation::Groundstation;
 use crate::helper::{self, nullpi, onepi, twopi};
+use crate::link_budget::LinkBudget;
+use crate::link_load::LoadReport;
+use crate::rain::{self, FadePolicy, RainModel, RainRate};
+use crate::relay::{self, Relay, RelayLinkRules};
 
 use crate::networkx_graph::{Graph as NxGraph, Node as NxNode};
 
+use crate::representations::doppler::Doppler;
+use crate::representations::link_diff::LinkDiff;
 use crate::representations::undirected_link::{LinkType, UndirectedLink};
-use crate::satellite::Satellite;
+use crate::robustness::{self, RobustnessMetrics};
+use crate::satellite::{OrbitShape, Satellite};
+use crate::simulator::{self, Flow, FlowStatistics, SimulationConfig};
+use crate::spatial_index::SatelliteIndex;
+use crate::temporal_graph::TemporalGraph;
+use crate::traffic::{self, Demand, TrafficModel};
+use crate::trajectory::{Trajectory, Waypoint};
+use crate::visibility::{self, VisibilityWindow};
 use itertools::Itertools;
-use nyx_space::time::{Duration, Epoch};
+use nyx_space::time::{Duration, Epoch, Unit};
+use nyx_space::Orbit;
 
+use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*;
 use rayon::prelude::*;
+use std::collections::{btree_map::Entry, BTreeMap, HashMap};
+use std::path::Path;
 use uom::si::angle::degree;
-use uom::si::f64::Time;
+use uom::si::f64::{Frequency, InformationRate, Time, Velocity};
+use uom::si::frequency::{gigahertz, hertz};
 
 use uom::si::time::millisecond;
+use uom::si::velocity::kilometer_per_hour;
 use uom::si::{
     f64::{Angle, Length},
     length::kilometer,
@@ -40,34 +65,474 @@ impl ConstellationType {
     }
 }
 
+/// Ground station given by its ID or its name.
+#[derive(Debug, Clone, FromPyObject)]
+pub enum GroundstationKey {
+    Id(u32),
+    Name(String),
+}
+
 #[pyclass(module = "constellation")]
 #[derive(Debug, Clone)]
 pub struct Constellation {
     constellation_type: ConstellationType,
     next_free_id: NodeId,
+    /// Number of slots of the Walker pattern, which may differ from the number of satellites
     number_of_satellites: u32,
     number_of_planes: u32,
+    /// Phasing parameter of the Walker pattern
+    inter_plane_spacing: u32,
+    /// Shape of the orbits of the shell
+    shape: OrbitShape,
+    inclination: Angle,
+    /// Satellites ordered by their IDs
     satellites: Vec<Satellite>,
+    /// Ground stations ordered by their IDs
     groundstations: Vec<Groundstation>,
     min_elevation: Angle,
-    links: Vec<UndirectedLink>,
+    /// Established links identified by the IDs of their nodes
+    links: BTreeMap<(NodeId, NodeId), UndirectedLink>,
+    /// ISLs between working satellites found by the last recalculation of the satellite connections,
+    /// cross-plane ISLs of Walker-Star constellations are marked since they depend on the latitudes
+    isl_candidates: Vec<((NodeId, NodeId), bool)>,
+    /// Links added and removed by the last propagation
+    last_diff: LinkDiff,
+    /// Weights of the links at their last topology event
+    tracker: TopologyTracker,
+    /// Epoch at which the constellation was created
+    start: Epoch,
     epoch: Epoch,
+    /// Carrier frequency used to compute the Doppler of each link
+    carrier_frequency: Option<Frequency>,
+    /// Link budget used for the capacity of ISLs
+    isl_budget: Option<LinkBudget>,
+    /// Link budget used for the capacity of GSLs
+    gsl_budget: Option<LinkBudget>,
+    /// Rain attenuation of the GSLs
+    rain: RainModel,
+    /// Failed satellites, ground stations, planes and ISLs
+    failures: FailureModel,
+    /// Relay satellites in higher orbits ordered by their IDs
+    relays: Vec<Relay>,
+    /// Rules for the links between LEO satellites and relays
+    relay_rules: RelayLinkRules,
 }
 
 #[pymethods]
 impl Constellation {
-    pub fn add_groundstation(&mut self, name: String, lat: f64, lon: f64, alt: f64) {
+    /// Adds a ground station at the given latitude (°), longitude (°) and altitude (km).
+    /// The weight (e.g., population or GDP) and the gateway role are used by the traffic models.
+    #[pyo3(signature = (name, lat, lon, alt, weight = 1.0, gateway = false))]
+    pub fn add_groundstation(
+        &mut self,
+        name: String,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        weight: f64,
+        gateway: bool,
+    ) -> PyResult<()> {
+        check_weight(weight)?;
         let lat: Angle = Angle::new::<degree>(lat);
         let lon: Angle = Angle::new::<degree>(lon);
         let alt: Length = Length::new::<kilometer>(alt);
         self.add_groundstation_lla(name, lat, lon, alt);
+        self.groundstations
+            .last_mut()
+            .unwrap()
+            .set_traffic(weight, gateway);
         self.recalculate_ground_visibilities();
+        Ok(())
+    }
+
+    /// Adds a satellite at the nominal position of the given empty slot of the Walker pattern
+    /// and returns its ID.
+    pub fn add_satellite(&mut self, plane: u32, slot: u32) -> PyResult<u32> {
+        self.check_slot(plane, Some(slot))?;
+        let id = self.next_id();
+        let satellite = self.nominal_satellite(id, plane, slot);
+        self.insert_satellite_node(satellite);
+        Ok(id.into())
+    }
+
+    /// Inserts a satellite into the given plane at the (mean) argument of latitude (°) on an orbit of the shape of the shell
+    /// with the (perigee) altitude (km), the one of the shell if not given, and returns its ID.
+    /// Satellites occupying an empty `slot` of the Walker pattern establish ISLs, spares without slot do not.
+    #[pyo3(signature = (plane, aol, altitude = None, slot = None))]
+    pub fn insert_satellite(
+        &mut self,
+        plane: u32,
+        aol: f64,
+        altitude: Option<f64>,
+        slot: Option<u32>,
+    ) -> PyResult<u32> {
+        self.check_slot(plane, slot)?;
+        if altitude.is_some_and(|altitude| !(altitude.is_finite() && altitude > 0.0)) {
+            return Err(PyValueError::new_err("altitude must be positive"));
+        }
+        let aol: Angle = Angle::new::<degree>(aol);
+        let shape = altitude.map_or(self.shape, |altitude| {
+            self.shape
+                .with_perigee_altitude(Length::new::<kilometer>(altitude))
+        });
+        Ok(self.insert_satellite_at(plane, slot, aol, shape).into())
+    }
+
+    /// Removes the satellite with the given ID, the IDs of all other nodes stay unchanged.
+    pub fn remove_satellite(&mut self, id: u32) -> PyResult<()> {
+        self.remove_satellite_node(id.into())
+            .map(|_| ())
+            .ok_or_else(|| PyValueError::new_err(format!("unknown satellite {}", id)))
+    }
+
+    /// Replaces the satellite with the given ID by a new satellite in the same orbit and slot
+    /// and returns the ID of the new satellite.
+    pub fn replace_satellite(&mut self, id: u32) -> PyResult<u32> {
+        let satellite = self
+            .remove_satellite_node(id.into())
+            .ok_or_else(|| PyValueError::new_err(format!("unknown satellite {}", id)))?;
+        let id = self.next_id();
+        self.insert_satellite_node(satellite.replaced(id));
+        Ok(id.into())
+    }
+
+    /// Adds a mobile terminal, e.g., an aircraft or a ship, following the given waypoints and returns its ID.
+    /// Each waypoint is given as (latitude (°), longitude (°), altitude (km), time in ms relative to the current epoch)
+    /// with strictly increasing times. Between the waypoints the terminal moves along the great circle,
+    /// before the first and after the last waypoint it stays at the respective waypoint.
+    #[pyo3(signature = (name, waypoints, weight = 1.0, gateway = false))]
+    pub fn add_terminal(
+        &mut self,
+        name: String,
+        waypoints: Vec<(f64, f64, f64, i32)>,
+        weight: f64,
+        gateway: bool,
+    ) -> PyResult<u32> {
+        check_weight(weight)?;
+        let waypoints = waypoints
+            .into_iter()
+            .map(|(lat, lon, alt, time)| {
+                Waypoint::new(
+                    Angle::new::<degree>(lat),
+                    Angle::new::<degree>(lon),
+                    Length::new::<kilometer>(alt),
+                    self.epoch + Duration::from_f64(time as f64, Unit::Millisecond),
+                )
+            })
+            .collect_vec();
+        let trajectory = Trajectory::new(waypoints).ok_or_else(|| {
+            PyValueError::new_err(
+                "waypoints must be non-empty, ordered by time and consecutive waypoints not antipodal",
+            )
+        })?;
+        Ok(self
+            .add_terminal_on(name, trajectory, weight, gateway)
+            .into())
+    }
+
+    /// Adds a mobile terminal travelling along the great circle from the start to the end (latitude (°), longitude (°))
+    /// at the altitude (km) with the speed (km/h) and returns its ID.
+    /// The terminal departs `departure` ms after the current epoch and stays at the end once arrived.
+    #[pyo3(signature = (name, start, end, alt, speed, departure = 0, weight = 1.0, gateway = false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_terminal_route(
+        &mut self,
+        name: String,
+        start: (f64, f64),
+        end: (f64, f64),
+        alt: f64,
+        speed: f64,
+        departure: i32,
+        weight: f64,
+        gateway: bool,
+    ) -> PyResult<u32> {
+        if speed <= 0.0 {
+            return Err(PyValueError::new_err("speed must be positive"));
+        }
+        check_weight(weight)?;
+        let position =
+            |(lat, lon): (f64, f64)| (Angle::new::<degree>(lat), Angle::new::<degree>(lon));
+        let trajectory = Trajectory::great_circle(
+            position(start),
+            position(end),
+            Length::new::<kilometer>(alt),
+            Velocity::new::<kilometer_per_hour>(speed),
+            self.epoch + Duration::from_f64(departure as f64, Unit::Millisecond),
+        )
+        .ok_or_else(|| PyValueError::new_err("start and end must not be antipodal"))?;
+        Ok(self
+            .add_terminal_on(name, trajectory, weight, gateway)
+            .into())
+    }
+
+    /// Adds a geostationary relay above the given longitude (°) and returns its ID.
+    /// The relay links up to `terminals` LEO satellites and all ground stations seeing it above the minimal elevation.
+    #[pyo3(signature = (lon, terminals = 4))]
+    pub fn add_geo_relay(&mut self, lon: f64, terminals: u32) -> u32 {
+        let lon: Angle = Angle::new::<degree>(lon);
+        let id = self.next_id();
+        self.add_relay_node(Relay::geostationary(id, lon, self.epoch, terminals))
+            .into()
+    }
+
+    /// Adds a relay, e.g., in MEO, on the orbit given by its Keplerian elements at the current epoch and returns its ID:
+    /// semi-major axis (km), eccentricity, inclination (°), RAAN (°), argument of perigee (°) and true anomaly (°).
+    /// The relay links up to `terminals` LEO satellites and all ground stations seeing it above the minimal elevation.
+    #[pyo3(signature = (sma, ecc, inc, raan, aop, ta, terminals = 4))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_relay(
+        &mut self,
+        sma: f64,
+        ecc: f64,
+        inc: f64,
+        raan: f64,
+        aop: f64,
+        ta: f64,
+        terminals: u32,
+    ) -> PyResult<u32> {
+        if !(0.0..1.0).contains(&ecc) || sma * (1.0 - ecc) <= relay::EARTH_RADIUS {
+            return Err(PyValueError::new_err(
+                "the orbit must be elliptic with its perigee above the earth",
+            ));
+        }
+        let orbit = Orbit::keplerian(
+            sma,
+            ecc,
+            inc,
+            raan,
+            aop,
+            ta,
+            self.epoch,
+            helper::earth_frame(),
+        );
+        let id = self.next_id();
+        Ok(self.add_relay_node(Relay::new(id, orbit, terminals)).into())
+    }
+
+    /// Sets the rules for the links between LEO satellites and relays: the maximal range in km, unlimited if not given,
+    /// the minimal altitude in km of the line of sight above the earth and the number of relay terminals of each LEO satellite.
+    #[pyo3(signature = (max_range = None, grazing_altitude = 100.0, satellite_terminals = 1))]
+    pub fn set_relay_link_rules(
+        &mut self,
+        max_range: Option<f64>,
+        grazing_altitude: f64,
+        satellite_terminals: u32,
+    ) {
+        self.relay_rules = RelayLinkRules::new(
+            max_range.map(Length::new::<kilometer>),
+            Length::new::<kilometer>(grazing_altitude),
+            satellite_terminals,
+        );
+        self.recalculate_relay_links();
+    }
+
+    /// Removes the ground station with the given ID or name, the IDs of all other nodes stay unchanged.
+    pub fn remove_groundstation(&mut self, groundstation: GroundstationKey) -> PyResult<()> {
+        let id = self.resolve_groundstation(groundstation)?;
+        self.remove_groundstation_by_id(id);
+        Ok(())
+    }
+
+    /// Moves the ground station with the given ID or name to the latitude (°), longitude (°) and altitude (km).
+    /// The ground station keeps its ID, name, traffic weight and role.
+    pub fn move_groundstation(
+        &mut self,
+        groundstation: GroundstationKey,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+    ) -> PyResult<()> {
+        let id = self.resolve_groundstation(groundstation)?;
+        let lat: Angle = Angle::new::<degree>(lat);
+        let lon: Angle = Angle::new::<degree>(lon);
+        let alt: Length = Length::new::<kilometer>(alt);
+        self.move_groundstation_lla(id, lat, lon, alt);
+        Ok(())
     }
 
     pub fn propagate(&mut self, step: i32) {
         let step: Time = Time::new::<millisecond>(step as f64);
         self.propagate_time(step);
     }
+
+    /// Propagates the constellation for the given step in ms and returns the changes of the topology:
+    /// links that went up or down and links whose weight changed more than the threshold in km
+    /// since their last event. Event times are given in ms since the creation of the constellation.
+    #[pyo3(signature = (step, threshold = 1.0))]
+    pub fn propagate_events(&mut self, step: i32, threshold: f64) -> EventStream {
+        let step: Time = Time::new::<millisecond>(step as f64);
+        let threshold: Length = Length::new::<kilometer>(threshold);
+        EventStream::new(self.propagate_with_events(step, threshold))
+    }
+
+    /// Sets the carrier frequency in GHz used for the Doppler of each link.
+    /// Passing `None` disables the Doppler computation.
+    pub fn set_carrier_frequency(&mut self, frequency: Option<f64>) {
+        let frequency: Option<Frequency> = frequency.map(Frequency::new::<gigahertz>);
+        self.set_carrier_frequency_hz(frequency);
+    }
+
+    /// Sets the link budget used for the capacity of ISLs and recalculates the satellite connections.
+    /// Passing `None` removes the capacity from the ISLs.
+    pub fn set_isl_link_budget(&mut self, budget: Option<LinkBudget>) {
+        self.isl_budget = budget;
+        self.recalculate_satellite_connections();
+    }
+
+    /// Sets the link budget used for the capacity of GSLs and recalculates the ground station visibilities.
+    /// Passing `None` removes the capacity from the GSLs.
+    pub fn set_gsl_link_budget(&mut self, budget: Option<LinkBudget>) {
+        self.gsl_budget = budget;
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Sets the fade margin in dB of the GSLs. Links whose rain attenuation exceeds the margin are removed,
+    /// or kept with the capacity reduced by the attenuation exceeding the margin if `remove` is false.
+    /// Attenuations within the margin do not reduce the capacity.
+    /// Passing `None` keeps all links and reduces their capacity by the whole attenuation.
+    #[pyo3(signature = (fade_margin, remove = true))]
+    pub fn set_rain_fade_margin(&mut self, fade_margin: Option<f64>, remove: bool) {
+        let policy = if remove {
+            FadePolicy::Remove
+        } else {
+            FadePolicy::Degrade
+        };
+        self.rain.set_fade_margin(fade_margin, policy);
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Sets a constant rain rate in mm/h at the ground station with the given name.
+    pub fn set_rain_rate(&mut self, groundstation: &str, rain_rate: f64) -> PyResult<()> {
+        let id = self.find_groundstation(groundstation)?;
+        self.rain.set_rain_rate(id, RainRate::Constant(rain_rate));
+        self.recalculate_ground_visibilities();
+        Ok(())
+    }
+
+    /// Sets the rain rate at the ground station with the given name
+    /// to the rate exceeded for 0.01% of the year in the given ITU-R P.837-1 rain climatic zone (A-Q).
+    pub fn set_rain_zone(&mut self, groundstation: &str, zone: char) -> PyResult<()> {
+        let rain_rate = rain::zone_rain_rate(zone)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown rain zone {}", zone)))?;
+        self.set_rain_rate(groundstation, rain_rate)
+    }
+
+    /// Loads rain rate time series from a CSV file with the columns `groundstation,time,rain_rate`.
+    /// Times are given in milliseconds relative to the current epoch, rain rates in mm/h.
+    pub fn load_rain_time_series(&mut self, path: &str) -> PyResult<()> {
+        let series = rain::read_time_series(Path::new(path), self.epoch)
+            .map_err(|err| PyIOError::new_err(err.to_string()))?;
+        for (name, rain_rate) in series {
+            let id = self.find_groundstation(&name)?;
+            self.rain.set_rain_rate(id, rain_rate);
+        }
+        self.recalculate_ground_visibilities();
+        Ok(())
+    }
+
+    /// Marks the satellite with the given ID as failed from `start` until `end`, both in ms relative to the current epoch.
+    /// Without `start` the failure begins immediately, without `end` it is permanent.
+    #[pyo3(signature = (id, start = None, end = None))]
+    pub fn fail_satellite(
+        &mut self,
+        id: u32,
+        start: Option<i32>,
+        end: Option<i32>,
+    ) -> PyResult<()> {
+        if !self.contains_satellite(id.into()) {
+            return Err(PyValueError::new_err(format!("unknown satellite {}", id)));
+        }
+        self.fail_in_interval(FailureTarget::Satellite(id.into()), start, end)
+    }
+
+    /// Marks the ground station with the given name as failed from `start` until `end` in ms,
+    /// see `fail_satellite`.
+    #[pyo3(signature = (groundstation, start = None, end = None))]
+    pub fn fail_groundstation(
+        &mut self,
+        groundstation: &str,
+        start: Option<i32>,
+        end: Option<i32>,
+    ) -> PyResult<()> {
+        let id = self.find_groundstation(groundstation)?;
+        self.fail_in_interval(FailureTarget::Groundstation(id), start, end)
+    }
+
+    /// Marks all satellites of the plane with the given index as failed from `start` until `end` in ms,
+    /// see `fail_satellite`.
+    #[pyo3(signature = (plane, start = None, end = None))]
+    pub fn fail_plane(&mut self, plane: u32, start: Option<i32>, end: Option<i32>) -> PyResult<()> {
+        if plane >= self.number_of_planes {
+            return Err(PyValueError::new_err(format!("unknown plane {}", plane)));
+        }
+        self.fail_in_interval(FailureTarget::Plane(plane), start, end)
+    }
+
+    /// Marks the ISL between the satellites with the given IDs as failed from `start` until `end` in ms,
+    /// see `fail_satellite`.
+    #[pyo3(signature = (first, second, start = None, end = None))]
+    pub fn fail_isl(
+        &mut self,
+        first: u32,
+        second: u32,
+        start: Option<i32>,
+        end: Option<i32>,
+    ) -> PyResult<()> {
+        if !self.contains_satellite(first.into())
+            || !self.contains_satellite(second.into())
+            || first == second
+        {
+            return Err(PyValueError::new_err(format!(
+                "no ISL between {} and {}",
+                first, second
+            )));
+        }
+        self.fail_in_interval(FailureTarget::isl(first.into(), second.into()), start, end)
+    }
+
+    /// Injects random failures of all satellites, and of all ISLs if `isls` is set, over the duration in ms.
+    /// Each element fails with the given rate in failures per day. Failed elements are repaired after
+    /// `repair_time` in ms and may fail again, without repair time they fail permanently.
+    /// The failures only depend on the seed.
+    #[pyo3(signature = (rate, duration, seed = 0, repair_time = None, isls = false))]
+    pub fn inject_random_failures(
+        &mut self,
+        rate: f64,
+        duration: i32,
+        seed: u64,
+        repair_time: Option<i32>,
+        isls: bool,
+    ) -> PyResult<()> {
+        if rate <= 0.0 {
+            return Err(PyValueError::new_err("failure rate must be positive"));
+        }
+        if repair_time.is_some_and(|repair_time| repair_time <= 0) {
+            return Err(PyValueError::new_err("repair time must be positive"));
+        }
+        let rate: Frequency = Frequency::new::<hertz>(rate / 86_400.0);
+        let duration: Time = Time::new::<millisecond>(duration as f64);
+        let repair_time: Option<Time> =
+            repair_time.map(|repair_time| Time::new::<millisecond>(repair_time as f64));
+        self.add_random_failures(rate, duration, repair_time, isls, seed);
+        Ok(())
+    }
+
+    /// Repairs all failed elements.
+    pub fn clear_failures(&mut self) {
+        self.failures.clear();
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Returns all injected failures with their `kind`, `target` and times in ms relative to the current epoch.
+    /// The `end` of permanent failures is `None`.
+    pub fn get_failures(&self, py: Python<'_>) -> Vec<PyObject> {
+        self.failures
+            .get_failures()
+            .iter()
+            .map(|failure| failure.to_object(py, self.epoch))
+            .collect()
+    }
 }
 
 impl Constellation {
@@ -78,20 +543,20 @@ impl Constellation {
     ///
     /// * `number_of_satellites` - The number of satellites in the constellation.
     /// * `number_of_planes` - The number of planes in the constellation.
-    /// * `altitude` - The altitude of the shell.
+    /// * `shape` - The shape of the orbits, circular or eccentric, with its (perigee) altitude.
     /// * `inclination` - The inclination of the satellite orbits.
     ///
     /// # Panics
     ///
     /// Panics if the number of satellites is not divisible by the number of planes. <br/>
-    /// Will also panic if the number of planes or satellites is equal to 0, or if the the altitude is equal or below 0km.
+    /// Will also panic if the number of planes or satellites is equal to 0, or if the the (perigee) altitude is equal or below 0km.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         constellation_type: ConstellationType,
         number_of_satellites: u32,
         number_of_planes: u32,
         inter_plane_spacing: u32,
-        altitude: Length,
+        shape: OrbitShape,
         inclination: Angle,
         dt: Epoch,
         min_elevation: Angle,
@@ -99,96 +564,113 @@ impl Constellation {
         // validate arguments
         assert!(number_of_satellites > 0);
         assert!(number_of_planes > 0);
-        assert!(number_of_satellites % number_of_planes == 0);
-        assert!(altitude.get::<kilometer>() > 0.0);
+        assert!(number_of_satellites.is_multiple_of(number_of_planes));
+        assert!(shape.get_perigee_altitude().get::<kilometer>() > 0.0);
 
-        let frame = helper::earth_frame();
         let sats_per_plane = number_of_satellites / number_of_planes;
 
-        // ΔΩ = 2𝜋/𝑃 in [0,2𝜋]
-        let raan_delta: Angle = constellation_type.get_raan_delta(number_of_planes);
-        // ΔΦ = 2𝜋/Q in [0,2𝜋]
-        let phase_difference: Angle = twopi() / sats_per_plane as f64;
-        // Δ𝑓 = 2𝜋𝐹/𝑃𝑄 in [0,2𝜋)
-        let phase_offset: Angle =
-            (twopi() * inter_plane_spacing as f64) / number_of_satellites as f64;
-        assert!(raan_delta >= nullpi() && raan_delta <= twopi());
-        assert!(phase_difference >= nullpi() && phase_difference <= twopi());
-        assert!(phase_offset >= nullpi() && phase_offset < twopi());
-
-        // create satellites
-        let mut satellites = Vec::with_capacity(number_of_satellites as usize);
-        // iterate over planes
-        for plane in 0..number_of_planes {
-            // calculate and validate raan of this plane
-            let raan: Angle = raan_delta * plane as f64;
-            assert!(raan >= nullpi() && raan <= twopi());
-            // the phasing offset of this plane which depends on Δ𝑓 and the index of the plane
-            let plane_phase_offset: Angle = phase_offset * plane as f64;
-            // iterate over satellites in plane
-            for number_in_plane in 0..sats_per_plane {
-                let id = NodeId(number_in_plane + plane * sats_per_plane);
-                // phase offset for this satellite
-                let sat_phase: Angle = phase_difference * number_in_plane as f64;
-                // argument of latitude is equal to the base offset of this plane + the phase of the satellite, mod 360.0
-                let aol: Angle = (plane_phase_offset + sat_phase) % twopi();
-                assert!(aol >= nullpi() && aol < twopi());
-
-                // println!(
-                //     "Create satellite({}-{}) at RAAN({}°), AOL({}°). ID: {}, TN: {:?}, BN: {:?}, LN: {:?}, RN: {:?}",
-                //     plane,
-                //     number_in_plane,
-                //     raan.get::<degree>(),
-                //     aol.get::<degree>(),
-                //     id,
-                //     top_neighbor,
-                //     bottom_neighbor,
-                //     left_neighbor,
-                //     right_neighbor
-                // );
-
-                let satellite = Satellite::new(
-                    id,
-                    aol,
-                    raan,
-                    plane,
-                    number_in_plane,
-                    altitude,
-                    inclination,
-                    dt,
-                    frame,
-                );
-                satellites.push(satellite);
-            }
-        }
-
         // create constellation
         let mut constellation = Constellation {
             constellation_type,
             next_free_id: number_of_satellites.into(),
             number_of_satellites,
             number_of_planes,
-            satellites,
+            inter_plane_spacing,
+            shape,
+            inclination,
+            satellites: Vec::with_capacity(number_of_satellites as usize),
             groundstations: vec![],
             min_elevation,
-            links: vec![],
+            links: BTreeMap::new(),
+            isl_candidates: vec![],
+            last_diff: LinkDiff::default(),
+            tracker: TopologyTracker::default(),
+            start: dt,
             epoch: dt,
+            carrier_frequency: None,
+            isl_budget: None,
+            gsl_budget: None,
+            rain: RainModel::default(),
+            failures: FailureModel::default(),
+            relays: vec![],
+            relay_rules: RelayLinkRules::default(),
         };
+
+        // create satellites
+        // iterate over planes
+        for plane in 0..number_of_planes {
+            // iterate over satellites in plane
+            for number_in_plane in 0..sats_per_plane {
+                let id = NodeId(number_in_plane + plane * sats_per_plane);
+                let satellite = constellation.nominal_satellite(id, plane, number_in_plane);
+                constellation.satellites.push(satellite);
+            }
+        }
         constellation.recalculate_satellite_connections();
+        constellation.last_diff = LinkDiff::default();
         constellation
     }
 
+    /// Creates a satellite with the given ID in the given slot of the Walker pattern,
+    /// propagated to the current epoch.
+    fn nominal_satellite(&self, id: NodeId, plane: u32, number_in_plane: u32) -> Satellite {
+        let sats_per_plane = self.number_of_satellites / self.number_of_planes;
+
+        // ΔΩ = 2𝜋/𝑃 in [0,2𝜋]
+        let raan_delta: Angle = self
+            .constellation_type
+            .get_raan_delta(self.number_of_planes);
+        // ΔΦ = 2𝜋/Q in [0,2𝜋]
+        let phase_difference: Angle = twopi() / sats_per_plane as f64;
+        // Δ𝑓 = 2𝜋𝐹/𝑃𝑄 in [0,2𝜋)
+        let phase_offset: Angle =
+            (twopi() * self.inter_plane_spacing as f64) / self.number_of_satellites as f64;
+        assert!(raan_delta >= nullpi() && raan_delta <= twopi());
+        assert!(phase_difference >= nullpi() && phase_difference <= twopi());
+        assert!(phase_offset >= nullpi() && phase_offset < twopi());
+
+        // calculate and validate raan of this plane
+        let raan: Angle = raan_delta * plane as f64;
+        assert!(raan >= nullpi() && raan <= twopi());
+        // the phasing offset of this plane which depends on Δ𝑓 and the index of the plane
+        let plane_phase_offset: Angle = phase_offset * plane as f64;
+        // phase offset for this satellite
+        let sat_phase: Angle = phase_difference * number_in_plane as f64;
+        // argument of latitude is equal to the base offset of this plane + the phase of the satellite, mod 360.0
+        let aol: Angle = (plane_phase_offset + sat_phase) % twopi();
+        assert!(aol >= nullpi() && aol < twopi());
+
+        let mut satellite = Satellite::new(
+            id,
+            aol,
+            raan,
+            plane,
+            Some(number_in_plane),
+            self.shape,
+            self.inclination,
+            self.start,
+            helper::earth_frame(),
+        );
+        satellite.propagate(helper::time(self.epoch - self.start));
+        satellite
+    }
+
     /// Returns the number of nodes in this constellation,
-    /// which is the sum of satellites and groundstations.
+    /// which is the sum of satellites, groundstations and relays.
     pub fn node_count(&self) -> u32 {
-        (self.satellites.len() + self.groundstations.len())
+        (self.satellites.len() + self.groundstations.len() + self.relays.len())
             .try_into()
             .unwrap()
     }
 
     /// Propagates all satellites in this constellation for the given step.
-    /// Recalculates the satellite connections and ground station visibilities.
-    pub fn propagate_time(&mut self, step: Time) {
+    /// Updates the satellite connections and ground station visibilities
+    /// and returns the links that were added and removed by this step.
+    /// Unless a failure starts or ends during the step, the ISL topology is kept:
+    /// established ISLs only get their distances and properties updated in place,
+    /// just the cross-plane ISLs of Walker-Star constellations are checked again.
+    pub fn propagate_time(&mut self, step: Time) -> LinkDiff {
+        let previous = self.epoch;
         // increase epoch
         self.epoch += Duration::from_f64(
             step.get::<millisecond>(),
@@ -197,23 +679,43 @@ impl Constellation {
         self.satellites
             .par_iter_mut()
             .for_each(|sat| sat.propagate(step));
+        self.relays
+            .par_iter_mut()
+            .for_each(|relay| relay.propagate(step));
         self.groundstations
             .par_iter_mut()
             .for_each(|gs| gs.update_epoch(self.epoch));
-        self.recalculate_satellite_connections();
-        self.recalculate_ground_visibilities();
+        self.last_diff = LinkDiff::default();
+        let mut diff = if self.failures.changes_between(previous, self.epoch) {
+            self.recalculate_satellite_connections()
+        } else {
+            self.update_satellite_connections()
+        };
+        diff.merge(self.recalculate_ground_visibilities());
+        diff
+    }
+
+    /// Propagates the constellation for the given step and returns the topology events of this step.
+    /// Weight changes are reported once the weight of a link differs more than the threshold from its last event.
+    pub fn propagate_with_events(&mut self, step: Time, threshold: Length) -> Vec<TopologyEvent> {
+        let diff = self.propagate_time(step);
+        let time = helper::time(self.epoch - self.start).get::<millisecond>();
+        self.tracker
+            .events(time, &diff, self.links.values(), threshold)
+    }
+
+    /// Returns the links that were added and removed by the last propagation
+    /// and by the changes of the constellation since then, e.g., added ground stations or failures.
+    pub fn get_last_diff(&self) -> &LinkDiff {
+        &self.last_diff
     }
 
     /// Calculates the distance between two nodes given by their IDs.
+    /// Both positions are expressed in the inertial frame.
     pub fn distance(&self, first: NodeId, second: NodeId) -> Length {
-        let first = self.get_node(first).get_position_ecef();
-        let second = self.get_node(second).get_position_ecef();
-
-        let fp = (first.get_x(), first.get_y(), first.get_z());
-        let sp = (second.get_x(), second.get_y(), second.get_z());
-        Length::new::<kilometer>(f64::sqrt(
-            (fp.0 - sp.0).powf(2.0) + (fp.1 - sp.1).powf(2.0) + (fp.2 - sp.2).powf(2.0),
-        ))
+        let first = self.get_node(first).get_orbit();
+        let second = self.get_node(second).get_orbit();
+        Length::new::<kilometer>((first.radius() - second.radius()).norm())
     }
 
     /// Adds a ground station to the constellation.
@@ -232,21 +734,396 @@ impl Constellation {
         self.groundstations.push(groundstation);
     }
 
-    /// Recalculates the visibility of the satellites for the constellation ground stations using the minimal elevation assigned to the constellation.
-    pub(crate) fn recalculate_ground_visibilities(&mut self) {
-        self.links.retain(|link| link.link_type() == LinkType::ISL);
-        let mut pairs: Vec<UndirectedLink> = self
+    /// Adds a mobile terminal following the given trajectory with the traffic weight and role
+    /// used by the traffic models and returns its ID.
+    /// The terminal is assigned the next free ID and establishes GSLs like a ground station.
+    pub fn add_terminal_on(
+        &mut self,
+        name: String,
+        trajectory: Trajectory,
+        weight: f64,
+        gateway: bool,
+    ) -> NodeId {
+        let id = self.next_id();
+        let (lat, lon, alt) = trajectory.position(self.epoch);
+        let mut terminal =
+            Groundstation::new(id, name, self.epoch, lat, lon, alt, self.min_elevation);
+        terminal.set_traffic(weight, gateway);
+        terminal.set_trajectory(trajectory);
+        self.groundstations.push(terminal);
+        self.recalculate_ground_visibilities();
+        id
+    }
+
+    /// Adds the relay, which must have the largest ID, and recalculates its links.
+    /// Returns the ID of the relay.
+    pub fn add_relay_node(&mut self, relay: Relay) -> NodeId {
+        let id = relay.get_id();
+        assert!(self.next_free_id > id && self.relays.last().is_none_or(|last| last.get_id() < id));
+        self.relays.push(relay);
+        self.recalculate_relay_links();
+        self.recalculate_ground_visibilities();
+        id
+    }
+
+    /// Removes the ground station with the given ID and its GSLs.
+    /// Returns whether the ground station was part of the constellation.
+    pub fn remove_groundstation_by_id(&mut self, id: NodeId) -> bool {
+        let Ok(index) = self
+            .groundstations
+            .binary_search_by_key(&id, |gs| gs.get_id())
+        else {
+            return false;
+        };
+        self.groundstations.remove(index);
+        self.rain.remove_rain_rate(id);
+        self.recalculate_ground_visibilities();
+        true
+    }
+
+    /// Moves the ground station with the given ID to the given position and recalculates its GSLs.
+    /// Returns whether the ground station was part of the constellation.
+    pub fn move_groundstation_lla(
+        &mut self,
+        id: NodeId,
+        lat: Angle,
+        lon: Angle,
+        alt: Length,
+    ) -> bool {
+        let Ok(index) = self
             .groundstations
+            .binary_search_by_key(&id, |gs| gs.get_id())
+        else {
+            return false;
+        };
+        self.groundstations[index] = self.groundstations[index].relocated(lat, lon, alt);
+        self.recalculate_ground_visibilities();
+        true
+    }
+
+    /// Inserts a satellite with the next free ID into the given plane at the (mean) argument of latitude
+    /// on an orbit of the given shape and returns its ID. The satellite occupies the given slot of the Walker pattern, if any.
+    pub fn insert_satellite_at(
+        &mut self,
+        plane: u32,
+        slot: Option<u32>,
+        aol: Angle,
+        shape: OrbitShape,
+    ) -> NodeId {
+        let id = self.next_id();
+        let raan: Angle = self
+            .constellation_type
+            .get_raan_delta(self.number_of_planes)
+            * plane as f64;
+        let satellite = Satellite::new(
+            id,
+            aol % twopi(),
+            raan,
+            plane,
+            slot,
+            shape,
+            self.inclination,
+            self.epoch,
+            helper::earth_frame(),
+        );
+        self.insert_satellite_node(satellite);
+        id
+    }
+
+    /// Removes the satellite with the given ID and returns it, if it is part of the constellation.
+    pub fn remove_satellite_node(&mut self, id: NodeId) -> Option<Satellite> {
+        let index = self
+            .satellites
+            .binary_search_by_key(&id, |sat| sat.get_id())
+            .ok()?;
+        let satellite = self.satellites.remove(index);
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+        Some(satellite)
+    }
+
+    /// Computes the ground tracks of all satellites, or only of those in the given plane,
+    /// starting at the current epoch and lasting for the given duration sampled with the given step.
+    pub fn ground_tracks(
+        &self,
+        plane: Option<u32>,
+        duration: Time,
+        step: Time,
+    ) -> Vec<GroundTrack> {
+        ground_track::ground_tracks(&self.satellites, plane, duration, step)
+    }
+
+    /// Computes the coverage of the given (latitude, longitude) points with the given minimal elevation,
+    /// or the one of the constellation if not given, starting at the current epoch and lasting for the given duration.
+    pub fn coverage(
+        &self,
+        points: &[(Angle, Angle)],
+        min_elevation: Option<Angle>,
+        duration: Time,
+        step: Time,
+    ) -> Vec<CoverageStatistics> {
+        let min_elevation = min_elevation.unwrap_or(self.min_elevation);
+        coverage::analyze_coverage(&self.satellites, points, min_elevation, duration, step)
+    }
+
+    /// Samples the links starting at the current epoch and lasting for the given duration into a time-expanded graph.
+    pub fn temporal_graph(&self, duration: Time, step: Time) -> TemporalGraph {
+        TemporalGraph::new(self, duration, step)
+    }
+
+    /// Derives the unidirectional contacts of all links starting at the current epoch and lasting for the given duration.
+    /// The links are sampled with the given step, links without link budget use the given rate.
+    pub fn contacts(&self, duration: Time, step: Time, rate: InformationRate) -> Vec<Contact> {
+        contact_plan::contacts(&self.temporal_graph(duration, step), rate)
+    }
+
+    /// Generates the traffic demands between the ground stations following the given model,
+    /// scaled to sum up to the given total rate.
+    pub fn traffic_matrix(&self, model: TrafficModel, total: InformationRate) -> Vec<Demand> {
+        traffic::traffic_matrix(&self.groundstations, model, total)
+    }
+
+    /// Computes the maximum flow from the source to the destination over the current links
+    /// and the minimum cut separating them. Links without link budget offer the given capacity.
+    pub fn max_flow(
+        &self,
+        source: NodeId,
+        destination: NodeId,
+        default_capacity: InformationRate,
+    ) -> MaxFlow {
+        flow::max_flow(&Network::new(self, default_capacity), source, destination)
+    }
+
+    /// Approximates the largest share of all demands that can be routed simultaneously over the current links,
+    /// within a factor of about (1 - 3ε) of the optimum. Links without link budget offer the given capacity.
+    pub fn max_concurrent_flow(
+        &self,
+        demands: &[Demand],
+        default_capacity: InformationRate,
+        epsilon: f64,
+    ) -> MultiCommodityFlow {
+        flow::max_concurrent_flow(&Network::new(self, default_capacity), demands, epsilon)
+    }
+
+    /// Routes the demands along the shortest paths at each time step, starting at the current epoch
+    /// and lasting for the given duration, and reports the resulting link loads.
+    pub fn link_loads(&self, demands: &[Demand], duration: Time, step: Time) -> LoadReport {
+        LoadReport::new(self, demands, duration, step)
+    }
+
+    /// Marks the element as failed from the start until the end offset relative to the current epoch,
+    /// or permanently without end. Failed elements are excluded from the links.
+    pub fn add_failure(&mut self, target: FailureTarget, start: Time, end: Option<Time>) {
+        let epoch = |offset: Time| {
+            self.epoch + Duration::from_f64(offset.get::<millisecond>(), Unit::Millisecond)
+        };
+        self.failures
+            .add(Failure::new(target, epoch(start), end.map(epoch)));
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Draws random failures of all satellites, and of all ISLs if requested,
+    /// starting at the current epoch and lasting for the given duration.
+    pub fn add_random_failures(
+        &mut self,
+        rate: Frequency,
+        duration: Time,
+        repair_time: Option<Time>,
+        isls: bool,
+        seed: u64,
+    ) {
+        let sats_per_plane = self.number_of_satellites / self.number_of_planes;
+        let slots = self.slots();
+        let mut targets = self
+            .satellites
             .iter()
-            .cartesian_product(&self.satellites)
-            // .par_bridge()
-            .filter(|(gs, sat)| gs.is_visible(sat))
-            .map(|(gs, sat)| {
-                let distance: Length = self.distance(gs.get_id(), sat.get_id());
-                UndirectedLink::new_gsl(gs.get_id(), sat.get_id(), distance)
+            .map(|sat| FailureTarget::Satellite(sat.get_id()))
+            .collect_vec();
+        if isls {
+            targets.extend(self.satellites.iter().flat_map(|sat| {
+                let neighbors = sat.get_neighbors(sats_per_plane, self.number_of_planes, &slots);
+                [neighbors.get_top(), neighbors.get_right()]
+                    .into_iter()
+                    .flatten()
+                    .map(move |neighbor| FailureTarget::isl(neighbors.get_id(), neighbor))
+            }));
+        }
+        for failure in
+            failures::random_failures(&targets, rate, self.epoch, duration, repair_time, seed)
+        {
+            self.failures.add(failure);
+        }
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Checks whether the node is failed at the current epoch.
+    pub fn is_failed(&self, id: NodeId) -> bool {
+        let plane = self.find_satellite(id).map(|sat| sat.get_plane());
+        self.failures.is_node_failed(id, plane, self.epoch)
+    }
+
+    /// Computes the robustness metrics of the topology at each time step,
+    /// starting at the current epoch and lasting for the given duration.
+    /// Only the `top` nodes and links with the highest betweenness are reported.
+    pub fn robustness(&self, duration: Time, step: Time, top: usize) -> Vec<RobustnessMetrics> {
+        robustness::robustness(self, duration, step, top)
+    }
+
+    /// Simulates the given flows packet by packet over the topology starting at the current epoch.
+    pub fn simulate(&self, flows: &[Flow], config: &SimulationConfig) -> Vec<FlowStatistics> {
+        simulator::simulate(self, flows, config)
+    }
+
+    /// Computes the contact windows of all ground station/satellite pairs,
+    /// starting at the current epoch and lasting for the given duration.
+    /// The step is used to bracket the AOS/LOS epochs, which are then refined to the millisecond.
+    pub fn visibility_windows(&self, duration: Time, step: Time) -> Vec<VisibilityWindow> {
+        visibility::visibility_windows(&self.groundstations, &self.satellites, duration, step)
+    }
+
+    /// Sets the carrier frequency used for the Doppler of each link and recalculates all links.
+    pub fn set_carrier_frequency_hz(&mut self, frequency: Option<Frequency>) {
+        self.carrier_frequency = frequency;
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Attaches the configured link properties to the given link:
+    /// - the Doppler of the carrier frequency
+    /// - the result of the link budget of its link type
+    fn with_link_properties(&self, link: UndirectedLink) -> UndirectedLink {
+        let doppler = self.carrier_frequency.map(|carrier| {
+            let first = self.get_node(link.get_first());
+            let second = self.get_node(link.get_second());
+            Doppler::between(first, second, carrier)
+        });
+        let budget = match link.link_type() {
+            LinkType::ISL | LinkType::IOL => self.isl_budget.as_ref(),
+            LinkType::GSL => self.gsl_budget.as_ref(),
+        };
+        let elevation = link.get_look_angles().map(|look| look.get_elevation());
+        let rain_attenuation = self.rain_attenuation(&link);
+        let performance = budget.map(|budget| {
+            budget.evaluate(
+                link.get_distance(),
+                elevation,
+                rain_attenuation
+                    .map(|attenuation| self.rain.excess_attenuation(attenuation))
+                    .unwrap_or(0.0),
+            )
+        });
+        link.with_doppler(doppler)
+            .with_rain_attenuation(rain_attenuation)
+            .with_performance(performance)
+    }
+
+    /// Computes the rain attenuation in dB of a GSL whose ground station has a rain rate.
+    /// Uses the frequency of the GSL link budget, or the carrier frequency if no budget is set.
+    fn rain_attenuation(&self, link: &UndirectedLink) -> Option<f64> {
+        let look_angles = link.get_look_angles()?;
+        let frequency = self
+            .gsl_budget
+            .as_ref()
+            .map(|budget| budget.get_frequency())
+            .or(self.carrier_frequency)?;
+        let groundstation = self.get_groundstation(link.get_first());
+        let rain_rate = self.rain.rain_rate(groundstation.get_id(), self.epoch)?;
+        Some(rain::rain_attenuation(
+            groundstation.get_lat(),
+            groundstation.get_height(),
+            look_angles.get_elevation(),
+            frequency,
+            rain_rate,
+        ))
+    }
+
+    /// Marks the element as failed from `start` until `end`, both in ms relative to the current epoch.
+    fn fail_in_interval(
+        &mut self,
+        target: FailureTarget,
+        start: Option<i32>,
+        end: Option<i32>,
+    ) -> PyResult<()> {
+        let start = start.unwrap_or(0);
+        if end.is_some_and(|end| end <= start) {
+            return Err(PyValueError::new_err("failure must end after its start"));
+        }
+        let start: Time = Time::new::<millisecond>(start as f64);
+        let end: Option<Time> = end.map(|end| Time::new::<millisecond>(end as f64));
+        self.add_failure(target, start, end);
+        Ok(())
+    }
+
+    /// Returns the ID of the ground station given by its ID or name.
+    fn resolve_groundstation(&self, groundstation: GroundstationKey) -> PyResult<NodeId> {
+        match groundstation {
+            GroundstationKey::Id(id) => self
+                .find_groundstation_by_id(id.into())
+                .map(|gs| gs.get_id())
+                .ok_or_else(|| PyValueError::new_err(format!("unknown ground station {}", id))),
+            GroundstationKey::Name(name) => self.find_groundstation(&name),
+        }
+    }
+
+    /// Returns the ID of the ground station with the given name.
+    fn find_groundstation(&self, name: &str) -> PyResult<NodeId> {
+        self.groundstations
+            .iter()
+            .find(|gs| gs.get_name() == name)
+            .map(|gs| gs.get_id())
+            .ok_or_else(|| PyValueError::new_err(format!("unknown ground station {}", name)))
+    }
+
+    /// Returns all links that are currently established.
+    pub(crate) fn get_links(&self) -> impl Iterator<Item = &UndirectedLink> {
+        self.links.values()
+    }
+
+    /// Returns the ground-to-satellite links that are currently established.
+    pub(crate) fn get_gsls(&self) -> impl Iterator<Item = &UndirectedLink> {
+        self.links
+            .values()
+            .filter(|link| link.link_type() == LinkType::GSL)
+    }
+
+    /// Recalculates the visibility of the satellites and relays for the constellation ground stations using the minimal elevation assigned to the constellation.
+    /// Satellites out of range are pruned by a spatial index before the exact elevation check.
+    /// Returns the GSLs that were added and removed.
+    pub(crate) fn recalculate_ground_visibilities(&mut self) -> LinkDiff {
+        let index = SatelliteIndex::new(&self.satellites, &self.groundstations);
+        let satellites = &self.satellites;
+        let relays = &self.relays;
+        let links: Vec<UndirectedLink> = self
+            .groundstations
+            .par_iter()
+            .flat_map_iter(|gs| {
+                index
+                    .candidates(gs)
+                    .into_iter()
+                    .map(move |i| (gs, satellites[i].get_id(), satellites[i].get_orbit()))
+                    // relays are few, hence they are checked without index
+                    .chain(
+                        relays
+                            .iter()
+                            .map(move |relay| (gs, relay.get_id(), relay.get_orbit())),
+                    )
+            })
+            .filter(|(gs, id, _)| !self.is_failed(gs.get_id()) && !self.is_failed(*id))
+            .filter(|(gs, _, orbit)| gs.is_visible(orbit))
+            .map(|(gs, id, orbit)| {
+                let look_angles = gs.look_angles(&orbit);
+                UndirectedLink::new_gsl(gs.get_id(), id, look_angles)
+            })
+            .map(|link| self.with_link_properties(link))
+            .filter(|link| {
+                !link
+                    .get_rain_attenuation()
+                    .is_some_and(|attenuation| self.rain.is_removed(attenuation))
             })
             .collect();
-        self.links.append(&mut pairs);
+        self.update_links(LinkType::GSL, links)
     }
 
     /// Recalculates the connections between satellites and their distance.
@@ -254,76 +1131,200 @@ impl Constellation {
     /// Checks if satellites:
     /// - are flying in the same direction (ascending or descening)
     /// - if the latitude of each satellite in the pair is below 70°
-    pub(crate) fn recalculate_satellite_connections(&mut self) {
-        self.links.retain(|link| link.link_type() == LinkType::GSL);
+    ///
+    /// The links between the satellites and the relays are recalculated as well.
+    ///
+    /// Returns the ISLs and IOLs that were added and removed.
+    pub(crate) fn recalculate_satellite_connections(&mut self) -> LinkDiff {
         let sats_per_plane = self.number_of_satellites / self.number_of_planes;
-        let mut pairs: Vec<UndirectedLink> = self
+        let failed_isls = self.failures.failed_isls(self.epoch);
+        let slots = self.slots();
+        self.isl_candidates = self
             .satellites
-            .iter()
+            .par_iter()
             // get top and right neighbor
-            .map(|sat| sat.get_neighbors(sats_per_plane, self.number_of_planes))
-            // calculate distance and create link
-            .flat_map(|neighbors| {
+            .map(|sat| sat.get_neighbors(sats_per_plane, self.number_of_planes, &slots))
+            .flat_map_iter(|neighbors| {
                 let current_sat_id: NodeId = neighbors.get_id();
-                let mut links = vec![];
+                let mut candidates = vec![];
 
                 // top neighbor
-                let top_sat_id = neighbors.get_top();
-                let top_distance: Length = self.distance(current_sat_id, top_sat_id);
-                let top_link = UndirectedLink::new_isl(current_sat_id, top_sat_id, top_distance);
-                // println!("Adding link {}<->{}", current_sat_id, top_sat_id);
-                links.push(top_link);
-
-                // check link to right neighbor
-                let right_sat_id = neighbors.get_right();
-                if match self.constellation_type {
-                    ConstellationType::Star => {
-                        let current_sat = self.get_satellite(current_sat_id);
-                        let right_sat = self.get_satellite(right_sat_id);
-                        // get latitudes
-                        let current_sat_lat: Angle = current_sat.get_lat();
-                        let right_sat_lat: Angle = right_sat.get_lat();
-                        // get movements
-                        let current_sat_ascending = current_sat.is_ascending();
-                        let right_sat_ascending = right_sat.is_ascending();
-                        // check if:
-                        // - current sat is not in the last plane
-                        // - both satellites lats are below 70°
-                        // - both are moving in the same direction
-                        current_sat.get_plane() != self.number_of_planes - 1
-                            && current_sat_lat.abs() < Angle::new::<degree>(70.0)
-                            && right_sat_lat.abs() < Angle::new::<degree>(70.0)
-                            && current_sat_ascending == right_sat_ascending
+                if let Some(top_sat_id) = neighbors.get_top() {
+                    candidates.push(((current_sat_id, top_sat_id), false));
+                }
+
+                // right neighbor, satellites in the last plane of a Walker-Star have none
+                if let Some(right_sat_id) = neighbors.get_right() {
+                    match self.constellation_type {
+                        ConstellationType::Star => {
+                            if self.get_satellite(current_sat_id).get_plane()
+                                != self.number_of_planes - 1
+                            {
+                                candidates.push(((current_sat_id, right_sat_id), true));
+                            }
+                        }
+                        ConstellationType::Delta => {
+                            candidates.push(((current_sat_id, right_sat_id), false))
+                        }
                     }
-                    ConstellationType::Delta => true,
-                } {
-                    let right_distance: Length = self.distance(current_sat_id, right_sat_id);
-                    let right_link =
-                        UndirectedLink::new_isl(current_sat_id, right_sat_id, right_distance);
-                    links.push(right_link);
-                    // println!("Adding link {}<->{}", current_sat_id, right_sat_id);
                 }
 
-                links
+                candidates
+            })
+            .filter(|((first, second), _)| {
+                !failed_isls.contains(&(*first.min(second), *first.max(second)))
+                    && !self.is_failed(*first)
+                    && !self.is_failed(*second)
             })
             .collect();
-        self.links.append(&mut pairs);
+        let links: Vec<UndirectedLink> = self
+            .isl_candidates
+            .par_iter()
+            .filter_map(|candidate| self.candidate_isl(*candidate))
+            .collect();
+        let mut diff = self.update_links(LinkType::ISL, links);
+        diff.merge(self.recalculate_relay_links());
+        diff
+    }
+
+    /// Updates the ISLs of the last recalculation of the satellite connections,
+    /// which stay valid as long as no satellite is added or removed and no failure starts or ends.
+    /// Established ISLs are updated in place, only cross-plane ISLs of Walker-Star constellations are established or lost.
+    /// The links between the satellites and the relays are recalculated.
+    ///
+    /// Returns the ISLs and IOLs that were added and removed.
+    fn update_satellite_connections(&mut self) -> LinkDiff {
+        let updates: Vec<((NodeId, NodeId), Option<UndirectedLink>)> = self
+            .isl_candidates
+            .par_iter()
+            .map(|&((first, second), checked)| {
+                (
+                    (first.min(second), first.max(second)),
+                    self.candidate_isl(((first, second), checked)),
+                )
+            })
+            .collect();
+        let mut added = vec![];
+        let mut removed = vec![];
+        for (key, update) in updates {
+            match (update, self.links.entry(key)) {
+                (Some(link), Entry::Occupied(mut entry)) => {
+                    entry.insert(link);
+                }
+                (Some(link), Entry::Vacant(entry)) => {
+                    entry.insert(link);
+                    added.push(link);
+                }
+                (None, Entry::Occupied(entry)) => removed.push(entry.remove()),
+                (None, Entry::Vacant(_)) => {}
+            }
+        }
+        let mut diff = LinkDiff::new(added, removed);
+        self.last_diff.merge(diff.clone());
+        diff.merge(self.recalculate_relay_links());
+        diff
+    }
+
+    /// Creates the ISL of the candidate with its current distance and link properties.
+    /// Cross-plane candidates of Walker-Star constellations only yield a link if both satellites
+    /// are below 70° latitude and are flying in the same direction.
+    fn candidate_isl(
+        &self,
+        ((first, second), checked): ((NodeId, NodeId), bool),
+    ) -> Option<UndirectedLink> {
+        if checked {
+            let first_sat = self.get_satellite(first);
+            let second_sat = self.get_satellite(second);
+            if first_sat.get_lat().abs() >= Angle::new::<degree>(70.0)
+                || second_sat.get_lat().abs() >= Angle::new::<degree>(70.0)
+                || first_sat.is_ascending() != second_sat.is_ascending()
+            {
+                return None;
+            }
+        }
+        let distance: Length = self.distance(first, second);
+        Some(self.with_link_properties(UndirectedLink::new_isl(first, second, distance)))
+    }
+
+    /// Recalculates the inter-orbit links between the satellites and the relays, failed satellites have none.
+    /// Returns the IOLs that were added and removed.
+    fn recalculate_relay_links(&mut self) -> LinkDiff {
+        let satellites = self
+            .satellites
+            .iter()
+            .filter(|sat| !self.is_failed(sat.get_id()));
+        let links = relay::relay_links(&self.relays, satellites, &self.relay_rules)
+            .into_iter()
+            .map(|(relay, sat, distance)| UndirectedLink::new_iol(relay, sat, distance))
+            .map(|link| self.with_link_properties(link))
+            .collect();
+        self.update_links(LinkType::IOL, links)
     }
 
+    /// Replaces the established links of the given type by the given links.
+    /// Links that remain established are updated in place, only added and removed links change the set.
+    /// The changes are recorded in the diff since the last propagation.
+    fn update_links(&mut self, link_type: LinkType, links: Vec<UndirectedLink>) -> LinkDiff {
+        let links: BTreeMap<(NodeId, NodeId), UndirectedLink> = links
+            .into_iter()
+            .map(|link| (link.get_key(), link))
+            .collect();
+        let lost = self
+            .links
+            .iter()
+            .filter(|(key, link)| link.link_type() == link_type && !links.contains_key(key))
+            .map(|(key, _)| *key)
+            .collect_vec();
+        let removed = lost
+            .iter()
+            .filter_map(|key| self.links.remove(key))
+            .collect_vec();
+        let added = links
+            .into_iter()
+            .filter_map(|(key, link)| match self.links.insert(key, link) {
+                Some(_) => None,
+                None => Some(link),
+            })
+            .collect_vec();
+        let diff = LinkDiff::new(added, removed);
+        self.last_diff.merge(diff.clone());
+        diff
+    }
+
+    /// Returns the current epoch of the constellation.
+    pub(crate) fn get_epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Returns all nodes ordered by their IDs.
     pub(crate) fn get_nodes(&self) -> Vec<&dyn Node> {
-        (0..self.node_count())
-            .map_into::<NodeId>()
-            .map(|id| self.get_node(id))
+        self.satellites
+            .iter()
+            .map(|sat| sat as &dyn Node)
+            .chain(self.groundstations.iter().map(|gs| gs as &dyn Node))
+            .chain(self.relays.iter().map(|relay| relay as &dyn Node))
+            .sorted_by_key(|node| node.get_id())
             .collect_vec()
     }
 
+    /// Checks whether a node with the given ID is part of the constellation.
+    pub(crate) fn contains_node(&self, id: NodeId) -> bool {
+        self.find_satellite(id).is_some()
+            || self.find_groundstation_by_id(id).is_some()
+            || self.find_relay(id).is_some()
+    }
+
+    /// Checks whether a satellite with the given ID is part of the constellation.
+    pub(crate) fn contains_satellite(&self, id: NodeId) -> bool {
+        self.find_satellite(id).is_some()
+    }
+
     /// Returns the next free ID for further usage.
     ///
     /// ### Important (Side effect)
     /// This method increases the next free ID after returning the previous one.<br/>
-    /// Thus, the caller must make sure:
-    /// - no ID is assigned twice (is unique)
-    /// - each ID is actually assigned since otherwise there could be intermediate unused IDs
+    /// IDs are never reused, thus removed nodes leave gaps and the IDs of all other nodes stay valid.
+    /// The satellites and ground stations stay ordered by their IDs as new nodes are appended.
     fn next_id(&mut self) -> NodeId {
         let tmp = self.next_free_id;
         self.next_free_id = tmp.next();
@@ -331,26 +1332,85 @@ impl Constellation {
     }
 
     fn get_node(&self, id: NodeId) -> &dyn Node {
-        assert!(id < self.next_free_id);
-        if id < NodeId(self.number_of_satellites) {
-            self.get_satellite(id)
-        } else {
-            self.get_groundstation(id)
+        if let Some(satellite) = self.find_satellite(id) {
+            return satellite;
+        }
+        match self.find_relay(id) {
+            Some(relay) => relay,
+            None => self.get_groundstation(id),
         }
     }
 
+    /// Returns the relay with the given ID, the relays are ordered by their IDs.
+    fn find_relay(&self, id: NodeId) -> Option<&Relay> {
+        self.relays
+            .binary_search_by_key(&id, |relay| relay.get_id())
+            .ok()
+            .map(|index| &self.relays[index])
+    }
+
+    /// Returns the satellite with the given ID, the satellites are ordered by their IDs.
+    fn find_satellite(&self, id: NodeId) -> Option<&Satellite> {
+        self.satellites
+            .binary_search_by_key(&id, |sat| sat.get_id())
+            .ok()
+            .map(|index| &self.satellites[index])
+    }
+
+    /// Returns the ground station with the given ID, the ground stations are ordered by their IDs.
+    fn find_groundstation_by_id(&self, id: NodeId) -> Option<&Groundstation> {
+        self.groundstations
+            .binary_search_by_key(&id, |gs| gs.get_id())
+            .ok()
+            .map(|index| &self.groundstations[index])
+    }
+
     fn get_satellite(&self, id: NodeId) -> &Satellite {
-        assert!(id < NodeId(self.number_of_satellites));
-        let index = id.0 as usize;
-        self.satellites.get(index).unwrap()
+        self.find_satellite(id).unwrap()
     }
 
     fn get_groundstation(&self, id: NodeId) -> &Groundstation {
-        assert!(id >= NodeId(self.number_of_satellites));
-        assert!(id < self.next_free_id);
-        // calculated index in ground station vector
-        let index = (id.0 - self.number_of_satellites) as usize;
-        self.groundstations.get(index).unwrap()
+        self.find_groundstation_by_id(id).unwrap()
+    }
+
+    /// Adds the satellite, which must have the largest ID, and recalculates the links.
+    fn insert_satellite_node(&mut self, satellite: Satellite) {
+        assert!(self
+            .satellites
+            .last()
+            .is_none_or(|last| last.get_id() < satellite.get_id()));
+        self.satellites.push(satellite);
+        self.recalculate_satellite_connections();
+        self.recalculate_ground_visibilities();
+    }
+
+    /// Checks that the plane exists and the slot, if any, is an empty slot of the Walker pattern.
+    fn check_slot(&self, plane: u32, slot: Option<u32>) -> PyResult<()> {
+        let sats_per_plane = self.number_of_satellites / self.number_of_planes;
+        if plane >= self.number_of_planes {
+            return Err(PyValueError::new_err(format!("unknown plane {}", plane)));
+        }
+        match slot {
+            Some(slot) if slot >= sats_per_plane => Err(PyValueError::new_err(format!(
+                "unknown slot {} in plane {}",
+                slot, plane
+            ))),
+            Some(slot) if self.slots().contains_key(&(plane, slot)) => Err(PyValueError::new_err(
+                format!("slot {} in plane {} is occupied", slot, plane),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the IDs of the satellites occupying each (plane, slot) of the Walker pattern.
+    fn slots(&self) -> HashMap<(u32, u32), NodeId> {
+        self.satellites
+            .iter()
+            .filter_map(|sat| {
+                sat.number_in_plane()
+                    .map(|slot| ((sat.get_plane(), slot), sat.get_id()))
+            })
+            .collect()
     }
 }
 
@@ -369,9 +1429,39 @@ impl From<Constellation> for NxGraph {
                 .cloned()
                 .map_into::<NxNode>()
                 .collect_vec(),
+            value
+                .relays
+                .iter()
+                .cloned()
+                .map_into::<NxNode>()
+                .collect_vec(),
         ]
-        .concat();
-        let links = value.links.iter().cloned().map_into().collect_vec();
-        NxGraph::new(nodes, links)
+        .concat()
+        .into_iter()
+        .map(|node| NxNode {
+            failed: value.is_failed(node.id.into()),
+            ..node
+        })
+        .collect_vec();
+        let links = value.links.values().cloned().map_into().collect_vec();
+        let mut graph = NxGraph::new(nodes, links);
+        graph.graph.failed_links = value
+            .failures
+            .failed_isls(value.epoch)
+            .into_iter()
+            .map(|(first, second)| (first.into(), second.into()))
+            .collect();
+        graph
+    }
+}
+
+/// Checks that the traffic weight of a ground station is finite and not negative.
+fn check_weight(weight: f64) -> PyResult<()> {
+    if weight.is_finite() && weight >= 0.0 {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(
+            "weight must be finite and not negative",
+        ))
     }
 }
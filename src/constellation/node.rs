@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 
+use nyx_space::{linalg::Vector3, Orbit};
 use pyo3::{types::PyTuple, PyObject, Python, ToPyObject};
 
 use uom::si::{
@@ -7,9 +8,9 @@ use uom::si::{
     length::kilometer,
 };
 
-use crate::representations::lla::LLA;
+use crate::representations::{lla::LLA, reference_frame::ReferenceFrame};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct NodeId(pub u32);
 
 impl Display for NodeId {
@@ -40,6 +41,17 @@ impl From<NodeId> for u32 {
 pub(crate) enum NodeType {
     Satellite,
     Groundstation,
+    /// Mobile ground node, e.g., an aircraft or a ship
+    Terminal,
+    /// Satellite in a higher orbit relaying the traffic of the LEO satellites, e.g., in GEO or MEO
+    Relay,
+}
+
+impl NodeType {
+    /// Checks whether the node is located on the ground, i.e., a ground station or a mobile terminal.
+    pub(crate) fn is_ground(&self) -> bool {
+        matches!(self, NodeType::Groundstation | NodeType::Terminal)
+    }
 }
 
 impl From<NodeType> for char {
@@ -47,6 +59,8 @@ impl From<NodeType> for char {
         match typ {
             NodeType::Satellite => 'S',
             NodeType::Groundstation => 'G',
+            NodeType::Terminal => 'T',
+            NodeType::Relay => 'R',
         }
     }
 }
@@ -56,17 +70,24 @@ pub struct NodePosition {
     x: f64,
     y: f64,
     z: f64,
+    /// Frame in which the coordinates are expressed
+    frame: ReferenceFrame,
 }
 
 impl NodePosition {
-    pub fn new(x: Length, y: Length, z: Length) -> Self {
+    pub fn new(x: Length, y: Length, z: Length, frame: ReferenceFrame) -> Self {
         NodePosition {
             x: x.get::<kilometer>(),
             y: y.get::<kilometer>(),
             z: z.get::<kilometer>(),
+            frame,
         }
     }
 
+    pub fn get_frame(&self) -> ReferenceFrame {
+        self.frame
+    }
+
     pub fn get_x(&self) -> f64 {
         self.x
     }
@@ -89,10 +110,21 @@ impl ToPyObject for NodePosition {
 pub(crate) trait Node {
     fn get_id(&self) -> NodeId;
     fn get_node_type(&self) -> NodeType;
-    fn get_position_ecef(&self) -> NodePosition;
-    fn get_x(&self) -> Length;
-    fn get_y(&self) -> Length;
-    fn get_z(&self) -> Length;
+    /// Returns the state of the node in the inertial frame,
+    /// which is used for all distances and visibilities.
+    fn get_orbit(&self) -> Orbit;
+    /// Returns the inertial acceleration of the node in km/s².
+    fn get_acceleration(&self) -> Vector3<f64>;
+    /// Returns the position of the node expressed in the given frame.
+    fn get_position(&self, frame: ReferenceFrame) -> NodePosition {
+        let orbit = frame.transform(&self.get_orbit());
+        NodePosition::new(
+            Length::new::<kilometer>(orbit.x),
+            Length::new::<kilometer>(orbit.y),
+            Length::new::<kilometer>(orbit.z),
+            frame,
+        )
+    }
     fn get_position_lla(&self) -> LLA;
     fn get_lat(&self) -> Angle;
     fn get_lon(&self) -> Angle;
@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, io::Error, path::Path};
+
+use uom::si::{
+    f64::{InformationRate, Length, Time},
+    information_rate::byte_per_second,
+    length::kilometer,
+    time::second,
+};
+
+use crate::{
+    constellation::node::NodeId, helper::SPEED_OF_LIGHT,
+    representations::undirected_link::UndirectedLink, temporal_graph::TemporalGraph,
+};
+
+/// A unidirectional transmission opportunity between two nodes.
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    from: NodeId,
+    to: NodeId,
+    /// Offset of the first epoch of the contact relative to the start of the window
+    start: Time,
+    /// Offset at which the contact ends relative to the start of the window
+    end: Time,
+    /// Lowest rate of the link during the contact
+    rate: InformationRate,
+    /// Largest distance of the link during the contact
+    range: Length,
+}
+
+impl Contact {
+    pub fn get_from(&self) -> NodeId {
+        self.from
+    }
+
+    pub fn get_to(&self) -> NodeId {
+        self.to
+    }
+
+    pub fn get_start(&self) -> Time {
+        self.start
+    }
+
+    pub fn get_end(&self) -> Time {
+        self.end
+    }
+
+    pub fn get_rate(&self) -> InformationRate {
+        self.rate
+    }
+
+    /// Returns the one-way light time over the largest distance of the contact.
+    pub fn get_owlt(&self) -> Time {
+        Time::new::<second>(self.range.get::<kilometer>() / SPEED_OF_LIGHT)
+    }
+}
+
+/// A link interval that is still established at the current layer.
+struct OpenContact {
+    link: UndirectedLink,
+    start: Time,
+    rate: InformationRate,
+    range: Length,
+}
+
+impl OpenContact {
+    /// Closes the interval at the given end and creates the contacts of both directions.
+    fn close(self, end: Time) -> [Contact; 2] {
+        let contact = |from, to| Contact {
+            from,
+            to,
+            start: self.start,
+            end,
+            rate: self.rate,
+            range: self.range,
+        };
+        [
+            contact(self.link.get_first(), self.link.get_second()),
+            contact(self.link.get_second(), self.link.get_first()),
+        ]
+    }
+}
+
+/// Derives the contacts from the links of the time-expanded graph.
+///
+/// A link is assumed to be established from the first layer it appears in until the next layer it is missing in,
+/// or the end of the window. Its rate is the capacity of the link budget if available, the given rate otherwise.
+pub(crate) fn contacts(graph: &TemporalGraph, default_rate: InformationRate) -> Vec<Contact> {
+    let mut contacts = vec![];
+    let mut open: BTreeMap<(NodeId, NodeId), OpenContact> = BTreeMap::new();
+    for (time, links) in graph.get_times().iter().zip(graph.get_layers()) {
+        let mut established = BTreeMap::new();
+        for link in links {
+            let rate = link.get_capacity().unwrap_or(default_rate);
+            let range = link.get_distance();
+            let contact = match open.remove(&link.get_key()) {
+                Some(contact) => OpenContact {
+                    rate: if rate < contact.rate {
+                        rate
+                    } else {
+                        contact.rate
+                    },
+                    range: if range > contact.range {
+                        range
+                    } else {
+                        contact.range
+                    },
+                    ..contact
+                },
+                None => OpenContact {
+                    link: *link,
+                    start: *time,
+                    rate,
+                    range,
+                },
+            };
+            established.insert(link.get_key(), contact);
+        }
+        // links missing in this layer were lost since the previous one
+        contacts.extend(open.into_values().flat_map(|contact| contact.close(*time)));
+        open = established;
+    }
+    if let Some(end) = graph.get_times().last() {
+        contacts.extend(open.into_values().flat_map(|contact| contact.close(*end)));
+    }
+    contacts.retain(|contact| contact.end > contact.start);
+    contacts.sort_by(|a, b| {
+        a.start
+            .partial_cmp(&b.start)
+            .unwrap()
+            .then(a.from.cmp(&b.from))
+            .then(a.to.cmp(&b.to))
+    });
+    contacts
+}
+
+/// Formats the contacts as ION contact plan with a `a contact` line per contact
+/// and a `a range` line per contact of both directions, since ION applies ranges symmetrically.
+/// Times are given in whole seconds relative to the start of the window, narrowed to lie within the contact
+/// such that no capacity is advertised beyond it, contacts without a whole second are left out.
+/// Rates are given in whole bytes/s and ranges as one-way light time in whole seconds.
+/// Node numbers are the node IDs plus one, since ION reserves node number 0.
+pub(crate) fn ion_contact_plan(contacts: &[Contact]) -> String {
+    let mut plan = String::new();
+    for contact in contacts {
+        let start = contact.start.get::<second>().ceil();
+        let end = contact.end.get::<second>().floor();
+        if end <= start {
+            continue;
+        }
+        let from = contact.from.0 + 1;
+        let to = contact.to.0 + 1;
+        let rate = contact.rate.get::<byte_per_second>().round();
+        writeln!(plan, "a contact +{start} +{end} {from} {to} {rate}").unwrap();
+        if from < to {
+            let owlt = contact.get_owlt().get::<second>().ceil();
+            writeln!(plan, "a range +{start} +{end} {from} {to} {owlt}").unwrap();
+        }
+    }
+    plan
+}
+
+/// Writes the contacts as ION contact plan to the given file.
+pub(crate) fn write_contact_plan(path: &Path, contacts: &[Contact]) -> Result<(), Error> {
+    fs::write(path, ion_contact_plan(contacts))
+}
@@ -0,0 +1,181 @@
+use nyx_space::linalg::Vector3;
+use rayon::prelude::*;
+use uom::si::{
+    f64::{Angle, Length, Time},
+    time::millisecond,
+};
+
+use crate::{
+    constellation::node::Node, groundstation::Observer,
+    representations::reference_frame::ReferenceFrame, satellite::Satellite,
+};
+
+/// Coverage of a single point over a time window.
+#[derive(Debug, Clone, Copy)]
+pub struct CoverageStatistics {
+    /// Average number of visible satellites
+    mean_visible: f64,
+    min_visible: u32,
+    max_visible: u32,
+    /// Fraction of the samples in which at least one satellite is visible
+    coverage: f64,
+    /// Longest time without a visible satellite
+    max_gap: Time,
+    /// Average time without a visible satellite between two contacts
+    revisit_time: Time,
+}
+
+impl CoverageStatistics {
+    pub fn get_mean_visible(&self) -> f64 {
+        self.mean_visible
+    }
+
+    pub fn get_min_visible(&self) -> u32 {
+        self.min_visible
+    }
+
+    pub fn get_max_visible(&self) -> u32 {
+        self.max_visible
+    }
+
+    pub fn get_coverage(&self) -> f64 {
+        self.coverage
+    }
+
+    pub fn get_max_gap(&self) -> Time {
+        self.max_gap
+    }
+
+    pub fn get_revisit_time(&self) -> Time {
+        self.revisit_time
+    }
+}
+
+/// Accumulates the visibility samples of a single point.
+#[derive(Debug, Clone, Copy, Default)]
+struct Accumulator {
+    samples: u32,
+    visible_sum: u64,
+    min_visible: Option<u32>,
+    max_visible: u32,
+    covered_samples: u32,
+    /// Time of the first sample without coverage of the current gap
+    gap_start: Option<Time>,
+    longest_gap: Time,
+    gap_sum: Time,
+    gaps: u32,
+}
+
+impl Accumulator {
+    fn add(&mut self, time: Time, visible: u32) {
+        self.samples += 1;
+        self.visible_sum += visible as u64;
+        self.min_visible = Some(self.min_visible.map_or(visible, |min| min.min(visible)));
+        self.max_visible = self.max_visible.max(visible);
+        if visible > 0 {
+            self.covered_samples += 1;
+            self.close_gap(time);
+        } else if self.gap_start.is_none() {
+            self.gap_start = Some(time);
+        }
+    }
+
+    /// Ends the current gap at the given time, gaps without duration are not counted.
+    fn close_gap(&mut self, end: Time) {
+        if let Some(start) = self.gap_start.take() {
+            let gap = end - start;
+            if gap > Time::default() {
+                if gap > self.longest_gap {
+                    self.longest_gap = gap;
+                }
+                self.gap_sum += gap;
+                self.gaps += 1;
+            }
+        }
+    }
+
+    /// Gaps last from their first sample without coverage until the next covered sample
+    /// or the end of the analyzed duration.
+    fn finish(mut self, duration: Time) -> CoverageStatistics {
+        self.close_gap(duration);
+        let samples = self.samples.max(1) as f64;
+        CoverageStatistics {
+            mean_visible: self.visible_sum as f64 / samples,
+            min_visible: self.min_visible.unwrap_or(0),
+            max_visible: self.max_visible,
+            coverage: self.covered_samples as f64 / samples,
+            max_gap: self.longest_gap,
+            revisit_time: if self.gaps > 0 {
+                self.gap_sum / self.gaps as f64
+            } else {
+                Time::new::<millisecond>(0.0)
+            },
+        }
+    }
+}
+
+/// Computes the coverage of the given points (latitude, longitude) on the earth surface
+/// by the given satellites, starting at their current epoch and lasting for the given duration.
+///
+/// The visibility is sampled with the given step, which also is the resolution of the gaps.
+/// Points are lightweight observers, hence they are not added as nodes to the constellation.
+pub(crate) fn analyze_coverage(
+    satellites: &[Satellite],
+    points: &[(Angle, Angle)],
+    min_elevation: Angle,
+    duration: Time,
+    step: Time,
+) -> Vec<CoverageStatistics> {
+    assert!(step.get::<millisecond>() > 0.0);
+    assert!(duration.get::<millisecond>() >= 0.0);
+
+    let mut satellites = satellites.to_vec();
+    let epoch = match satellites.first() {
+        Some(sat) => sat.get_orbit().dt,
+        None => return vec![Accumulator::default().finish(duration); points.len()],
+    };
+    let observers: Vec<Observer> = points
+        .iter()
+        .map(|(lat, lon)| Observer::new(*lat, *lon, Length::default(), epoch))
+        .collect();
+    let mut accumulators = vec![Accumulator::default(); points.len()];
+
+    let mut offset = Time::new::<millisecond>(0.0);
+    loop {
+        let positions: Vec<Vector3<f64>> = satellites
+            .par_iter()
+            .map(|sat| {
+                let position = sat.get_position(ReferenceFrame::ECEF);
+                Vector3::new(position.get_x(), position.get_y(), position.get_z())
+            })
+            .collect();
+        accumulators
+            .par_iter_mut()
+            .zip(observers.par_iter())
+            .for_each(|(accumulator, observer)| {
+                let visible = positions
+                    .iter()
+                    .filter(|position| observer.elevation(position) >= min_elevation)
+                    .count();
+                accumulator.add(offset, visible as u32);
+            });
+
+        if offset >= duration {
+            break;
+        }
+        let next = if offset + step > duration {
+            duration
+        } else {
+            offset + step
+        };
+        satellites
+            .par_iter_mut()
+            .for_each(|sat| sat.propagate(next - offset));
+        offset = next;
+    }
+
+    accumulators
+        .into_iter()
+        .map(|accumulator| accumulator.finish(duration))
+        .collect()
+}
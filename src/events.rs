@@ -0,0 +1,208 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+
+use pyo3::{exceptions::PyIOError, prelude::*, types::PyDict};
+use serde::Serialize;
+use uom::si::{f64::Length, length::kilometer};
+
+use crate::{
+    constellation::node::NodeId,
+    networkx_graph::Link as NxLink,
+    representations::{link_diff::LinkDiff, undirected_link::UndirectedLink},
+};
+
+/// Kind of a change of the topology.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyEventKind {
+    LinkUp,
+    LinkDown,
+    WeightChanged,
+}
+
+impl From<TopologyEventKind> for &'static str {
+    fn from(value: TopologyEventKind) -> Self {
+        match value {
+            TopologyEventKind::LinkUp => "link_up",
+            TopologyEventKind::LinkDown => "link_down",
+            TopologyEventKind::WeightChanged => "weight_changed",
+        }
+    }
+}
+
+/// A change of a single link between two time steps.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TopologyEvent {
+    /// Time in ms since the creation of the constellation
+    time: f64,
+    event: TopologyEventKind,
+    /// Weight reported by the previous event of the link (weight changes only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_weight: Option<i32>,
+    /// Attributes of the link, for lost links the ones of its last state
+    #[serde(flatten)]
+    link: NxLink,
+}
+
+impl TopologyEvent {
+    fn new(
+        time: f64,
+        event: TopologyEventKind,
+        link: &UndirectedLink,
+        previous_weight: Option<Length>,
+    ) -> TopologyEvent {
+        TopologyEvent {
+            time,
+            event,
+            previous_weight: previous_weight.map(|weight| weight.get::<kilometer>().round() as i32),
+            link: (*link).into(),
+        }
+    }
+}
+
+impl ToPyObject for TopologyEvent {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        let dict = PyDict::new(py);
+        dict.set_item("time", self.time).unwrap();
+        dict.set_item("event", <&str>::from(self.event)).unwrap();
+        dict.set_item("source", self.link.source).unwrap();
+        dict.set_item("target", self.link.target).unwrap();
+        dict.set_item("weight", self.link.weight).unwrap();
+        if let Some(previous_weight) = self.previous_weight {
+            dict.set_item("previous_weight", previous_weight).unwrap();
+        }
+        self.link
+            .attributes()
+            .for_each(|(key, value)| dict.set_item(key, value).unwrap());
+        dict.to_object(py)
+    }
+}
+
+/// Remembers the weight of each link at its last event to detect weight changes beyond a threshold.
+/// Slow drifts are thus reported as soon as they accumulate beyond the threshold.
+#[derive(Debug, Clone, Default)]
+pub struct TopologyTracker {
+    reported: HashMap<(NodeId, NodeId), Length>,
+}
+
+impl TopologyTracker {
+    /// Creates the events of the given diff and of all established links
+    /// whose weight differs more than the threshold from their last reported weight.
+    /// Links without a reported weight, e.g., established before tracking started, are recorded silently.
+    pub(crate) fn events<'a>(
+        &mut self,
+        time: f64,
+        diff: &LinkDiff,
+        links: impl Iterator<Item = &'a UndirectedLink>,
+        threshold: Length,
+    ) -> Vec<TopologyEvent> {
+        let mut events = vec![];
+        for link in diff.get_removed() {
+            self.reported.remove(&link.get_key());
+            events.push(TopologyEvent::new(
+                time,
+                TopologyEventKind::LinkDown,
+                link,
+                None,
+            ));
+        }
+        for link in diff.get_added() {
+            self.reported.insert(link.get_key(), link.get_distance());
+            events.push(TopologyEvent::new(
+                time,
+                TopologyEventKind::LinkUp,
+                link,
+                None,
+            ));
+        }
+        let mut established = HashMap::with_capacity(self.reported.len());
+        for link in links {
+            let key = link.get_key();
+            let weight = link.get_distance();
+            match self.reported.get(&key) {
+                Some(previous) if (weight - *previous).abs() > threshold => {
+                    events.push(TopologyEvent::new(
+                        time,
+                        TopologyEventKind::WeightChanged,
+                        link,
+                        Some(*previous),
+                    ));
+                    established.insert(key, weight);
+                }
+                Some(previous) => {
+                    established.insert(key, *previous);
+                }
+                None => {
+                    established.insert(key, weight);
+                }
+            }
+        }
+        // forget links lost while not tracking
+        self.reported = established;
+        events
+    }
+}
+
+/// Writes the events as JSON lines to the given file.
+/// With `append`, the events are added to the end of the file instead of replacing its content.
+pub(crate) fn write_events(
+    path: &Path,
+    events: &[TopologyEvent],
+    append: bool,
+) -> Result<(), Error> {
+    let file = File::options()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    let mut writer = BufWriter::new(file);
+    for event in events {
+        serde_json::to_writer(&mut writer, event)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Topology events of a propagation step, iterable from Python.
+#[pyclass(module = "events")]
+#[derive(Debug, Clone)]
+pub struct EventStream {
+    events: VecDeque<TopologyEvent>,
+}
+
+impl EventStream {
+    pub(crate) fn new(events: Vec<TopologyEvent>) -> EventStream {
+        EventStream {
+            events: events.into(),
+        }
+    }
+}
+
+#[pymethods]
+impl EventStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> Option<PyObject> {
+        slf.events.pop_front().map(|event| event.to_object(py))
+    }
+
+    fn __len__(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Writes the remaining events as JSON lines to the given file.
+    /// With `append`, the events are added to the end of an existing file.
+    #[pyo3(signature = (path, append = false))]
+    fn write(&self, path: &str, append: bool) -> PyResult<()> {
+        let events: Vec<TopologyEvent> = self.events.iter().copied().collect();
+        write_events(Path::new(path), &events, append)
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+}
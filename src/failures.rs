@@ -0,0 +1,153 @@
+use nyx_space::time::{Duration, Epoch, Unit};
+use pyo3::{types::PyDict, PyObject, Python, ToPyObject};
+use rand::{Rng, SeedableRng};
+use rand_distr::Exp;
+use rand_pcg::Pcg64;
+use uom::si::{
+    f64::{Frequency, Time},
+    frequency::hertz,
+    time::millisecond,
+};
+
+use crate::{constellation::node::NodeId, helper};
+
+/// Element of the constellation that can fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailureTarget {
+    Satellite(NodeId),
+    Groundstation(NodeId),
+    /// All satellites of the plane with the given index
+    Plane(u32),
+    /// ISL identified by the IDs of its satellites in ascending order
+    Isl(NodeId, NodeId),
+}
+
+impl FailureTarget {
+    /// Creates the failure target of the ISL between the given satellites.
+    pub fn isl(first: NodeId, second: NodeId) -> Self {
+        FailureTarget::Isl(first.min(second), first.max(second))
+    }
+}
+
+/// Failure of an element, either permanent or until the end epoch.
+#[derive(Debug, Clone, Copy)]
+pub struct Failure {
+    target: FailureTarget,
+    start: Epoch,
+    end: Option<Epoch>,
+}
+
+impl Failure {
+    pub fn new(target: FailureTarget, start: Epoch, end: Option<Epoch>) -> Self {
+        Failure { target, start, end }
+    }
+
+    /// Checks whether the element is failed at the given epoch.
+    pub fn is_active(&self, epoch: Epoch) -> bool {
+        self.start <= epoch && self.end.is_none_or(|end| epoch < end)
+    }
+
+    /// Converts the failure into a dict with times in ms relative to the given epoch.
+    pub(crate) fn to_object(self, py: Python<'_>, epoch: Epoch) -> PyObject {
+        let dict = PyDict::new(py);
+        let (kind, target) = match self.target {
+            FailureTarget::Satellite(id) => ("satellite", u32::from(id).to_object(py)),
+            FailureTarget::Groundstation(id) => ("groundstation", u32::from(id).to_object(py)),
+            FailureTarget::Plane(plane) => ("plane", plane.to_object(py)),
+            FailureTarget::Isl(first, second) => {
+                ("isl", (u32::from(first), u32::from(second)).to_object(py))
+            }
+        };
+        let offset = |epoch_at: Epoch| helper::time(epoch_at - epoch).get::<millisecond>();
+        dict.set_item("kind", kind).unwrap();
+        dict.set_item("target", target).unwrap();
+        dict.set_item("start", offset(self.start)).unwrap();
+        dict.set_item("end", self.end.map(offset)).unwrap();
+        dict.to_object(py)
+    }
+}
+
+/// Failures injected into the constellation.
+#[derive(Debug, Clone, Default)]
+pub struct FailureModel {
+    failures: Vec<Failure>,
+}
+
+impl FailureModel {
+    pub fn add(&mut self, failure: Failure) {
+        self.failures.push(failure);
+    }
+
+    /// Repairs all elements by removing all failures.
+    pub fn clear(&mut self) {
+        self.failures.clear();
+    }
+
+    pub fn get_failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    /// Checks whether the node, which belongs to the given plane if it is a satellite, is failed at the epoch.
+    pub fn is_node_failed(&self, node: NodeId, plane: Option<u32>, epoch: Epoch) -> bool {
+        self.failures.iter().any(|failure| {
+            failure.is_active(epoch)
+                && match failure.target {
+                    FailureTarget::Satellite(id) | FailureTarget::Groundstation(id) => id == node,
+                    FailureTarget::Plane(failed) => plane == Some(failed),
+                    FailureTarget::Isl(..) => false,
+                }
+        })
+    }
+
+    /// Checks whether any element fails or is repaired between the two epochs.
+    pub fn changes_between(&self, from: Epoch, to: Epoch) -> bool {
+        self.failures
+            .iter()
+            .any(|failure| failure.is_active(from) != failure.is_active(to))
+    }
+
+    /// Returns the ISLs failed at the epoch, regardless of the state of their satellites.
+    pub fn failed_isls(&self, epoch: Epoch) -> Vec<(NodeId, NodeId)> {
+        self.failures
+            .iter()
+            .filter(|failure| failure.is_active(epoch))
+            .filter_map(|failure| match failure.target {
+                FailureTarget::Isl(first, second) => Some((first, second)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Draws random failures of the given elements starting at the given epoch and lasting for the given duration.
+/// The time to failure of each element is exponentially distributed with the given failure rate.
+/// Elements are repaired after the repair time and may fail again, without repair time they fail permanently.
+/// The failures only depend on the seed. The failure rate and the repair time must be positive.
+pub(crate) fn random_failures(
+    targets: &[FailureTarget],
+    rate: Frequency,
+    start: Epoch,
+    duration: Time,
+    repair_time: Option<Time>,
+    seed: u64,
+) -> Vec<Failure> {
+    assert!(rate.get::<hertz>() > 0.0);
+    assert!(repair_time.is_none_or(|repair| repair.get::<millisecond>() > 0.0));
+    let mut rng = Pcg64::seed_from_u64(seed);
+    // times to failure in ms
+    let time_to_failure = Exp::new(rate.get::<hertz>() / 1000.0).unwrap();
+    let epoch = |offset: f64| start + Duration::from_f64(offset, Unit::Millisecond);
+    let mut failures = vec![];
+    for target in targets {
+        let mut offset: f64 = rng.sample(time_to_failure);
+        while offset < duration.get::<millisecond>() {
+            let end = repair_time.map(|repair| offset + repair.get::<millisecond>());
+            failures.push(Failure::new(*target, epoch(offset), end.map(epoch)));
+            match end {
+                Some(end) => offset = end + rng.sample(time_to_failure),
+                None => break,
+            }
+        }
+    }
+    failures
+}
@@ -0,0 +1,498 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use uom::si::{f64::InformationRate, information_rate::bit_per_second};
+
+use crate::{
+    constellation::{node::NodeId, Constellation},
+    traffic::Demand,
+};
+
+/// Relative tolerance below which residual capacities are considered exhausted.
+const CAPACITY_TOLERANCE: f64 = 1e-9;
+
+/// Directed arc of a link, each link offers its capacity in both directions.
+#[derive(Debug, Clone, Copy)]
+struct Arc {
+    from: usize,
+    to: usize,
+    /// Capacity in bit/s
+    capacity: f64,
+}
+
+/// Capacitated directed graph of the currently established links.
+/// Ground stations only send and receive traffic, they do not relay it.
+pub(crate) struct Network {
+    nodes: Vec<NodeId>,
+    indices: HashMap<NodeId, usize>,
+    arcs: Vec<Arc>,
+    outgoing: Vec<Vec<usize>>,
+    groundstations: HashSet<usize>,
+}
+
+impl Network {
+    /// Builds the network of the given constellation, links without link budget use the given capacity.
+    /// Links whose capacity is negligible relative to the largest capacity are left out.
+    pub(crate) fn new(constellation: &Constellation, default_capacity: InformationRate) -> Self {
+        let nodes = constellation.get_nodes();
+        let indices: HashMap<NodeId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.get_id(), i))
+            .collect();
+        let groundstations = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.get_node_type().is_ground())
+            .map(|(i, _)| i)
+            .collect();
+        let links: Vec<(NodeId, NodeId, f64)> = constellation
+            .get_links()
+            .map(|link| {
+                let capacity = link
+                    .get_capacity()
+                    .unwrap_or(default_capacity)
+                    .get::<bit_per_second>();
+                (link.get_first(), link.get_second(), capacity)
+            })
+            .collect();
+        let tolerance = links
+            .iter()
+            .map(|(_, _, capacity)| *capacity)
+            .fold(0.0, f64::max)
+            * CAPACITY_TOLERANCE;
+        let mut arcs = vec![];
+        let mut outgoing = vec![vec![]; nodes.len()];
+        for (first, second, capacity) in links {
+            if capacity <= tolerance {
+                continue;
+            }
+            let first = indices[&first];
+            let second = indices[&second];
+            for (from, to) in [(first, second), (second, first)] {
+                outgoing[from].push(arcs.len());
+                arcs.push(Arc { from, to, capacity });
+            }
+        }
+        Network {
+            nodes: nodes.iter().map(|node| node.get_id()).collect(),
+            indices,
+            arcs,
+            outgoing,
+            groundstations,
+        }
+    }
+
+    /// Checks whether traffic of the given source may leave the node.
+    fn may_forward(&self, node: usize, source: usize) -> bool {
+        node == source || !self.groundstations.contains(&node)
+    }
+}
+
+/// Result of a single-pair maximum flow.
+#[derive(Debug, Clone)]
+pub struct MaxFlow {
+    value: InformationRate,
+    /// Links of the minimum cut, given from the source to the destination side
+    cut: Vec<(NodeId, NodeId)>,
+    /// Nodes reachable from the source in the residual network
+    source_side: Vec<NodeId>,
+}
+
+impl MaxFlow {
+    pub fn get_value(&self) -> InformationRate {
+        self.value
+    }
+
+    pub fn get_cut(&self) -> &[(NodeId, NodeId)] {
+        &self.cut
+    }
+
+    pub fn get_source_side(&self) -> &[NodeId] {
+        &self.source_side
+    }
+}
+
+/// Computes the maximum flow from the source to the destination with Dinic's algorithm
+/// and the minimum cut separating them.
+pub(crate) fn max_flow(network: &Network, source: NodeId, destination: NodeId) -> MaxFlow {
+    let s = network.indices[&source];
+    let t = network.indices[&destination];
+    // residual graph: each arc is paired with its reverse arc
+    let mut residual: Vec<f64> = vec![];
+    let mut heads: Vec<usize> = vec![];
+    let mut adjacency: Vec<Vec<usize>> = vec![vec![]; network.nodes.len()];
+    for arc in network.arcs.iter() {
+        if !network.may_forward(arc.from, s) {
+            continue;
+        }
+        adjacency[arc.from].push(residual.len());
+        residual.push(arc.capacity);
+        heads.push(arc.to);
+        adjacency[arc.to].push(residual.len());
+        residual.push(0.0);
+        heads.push(arc.from);
+    }
+    let tolerance = network
+        .arcs
+        .iter()
+        .map(|arc| arc.capacity)
+        .fold(0.0, f64::max)
+        * CAPACITY_TOLERANCE;
+
+    let mut value = 0.0;
+    if s != t {
+        loop {
+            // build the level graph
+            let mut levels = vec![usize::MAX; network.nodes.len()];
+            levels[s] = 0;
+            let mut queue = VecDeque::from([s]);
+            while let Some(node) = queue.pop_front() {
+                for &edge in adjacency[node].iter() {
+                    if residual[edge] > tolerance && levels[heads[edge]] == usize::MAX {
+                        levels[heads[edge]] = levels[node] + 1;
+                        queue.push_back(heads[edge]);
+                    }
+                }
+            }
+            if levels[t] == usize::MAX {
+                break;
+            }
+            // send blocking flows along the level graph
+            let mut next = vec![0; network.nodes.len()];
+            loop {
+                let pushed = augment(
+                    s,
+                    t,
+                    f64::INFINITY,
+                    &levels,
+                    &mut next,
+                    &adjacency,
+                    &heads,
+                    &mut residual,
+                    tolerance,
+                );
+                if pushed <= tolerance {
+                    break;
+                }
+                value += pushed;
+            }
+        }
+    }
+
+    // minimum cut between the nodes reachable in the residual graph and the others
+    let mut reachable = vec![false; network.nodes.len()];
+    reachable[s] = true;
+    let mut queue = VecDeque::from([s]);
+    while let Some(node) = queue.pop_front() {
+        for &edge in adjacency[node].iter() {
+            if residual[edge] > tolerance && !reachable[heads[edge]] {
+                reachable[heads[edge]] = true;
+                queue.push_back(heads[edge]);
+            }
+        }
+    }
+    let cut = network
+        .arcs
+        .iter()
+        .filter(|arc| network.may_forward(arc.from, s))
+        .filter(|arc| reachable[arc.from] && !reachable[arc.to])
+        .map(|arc| (network.nodes[arc.from], network.nodes[arc.to]))
+        .collect();
+    let source_side = (0..network.nodes.len())
+        .filter(|node| reachable[*node])
+        .map(|node| network.nodes[node])
+        .collect();
+    MaxFlow {
+        value: InformationRate::new::<bit_per_second>(value),
+        cut,
+        source_side,
+    }
+}
+
+/// Pushes flow along a path of increasing levels by depth-first search.
+#[allow(clippy::too_many_arguments)]
+fn augment(
+    node: usize,
+    target: usize,
+    limit: f64,
+    levels: &[usize],
+    next: &mut [usize],
+    adjacency: &[Vec<usize>],
+    heads: &[usize],
+    residual: &mut [f64],
+    tolerance: f64,
+) -> f64 {
+    if node == target {
+        return limit;
+    }
+    while next[node] < adjacency[node].len() {
+        let edge = adjacency[node][next[node]];
+        let head = heads[edge];
+        if residual[edge] > tolerance && levels[head] == levels[node] + 1 {
+            let pushed = augment(
+                head,
+                target,
+                limit.min(residual[edge]),
+                levels,
+                next,
+                adjacency,
+                heads,
+                residual,
+                tolerance,
+            );
+            if pushed > tolerance {
+                residual[edge] -= pushed;
+                residual[edge ^ 1] += pushed;
+                return pushed;
+            }
+        }
+        next[node] += 1;
+    }
+    0.0
+}
+
+/// Result of a maximum concurrent multi-commodity flow.
+#[derive(Debug, Clone)]
+pub struct MultiCommodityFlow {
+    /// Largest share of every demand that can be routed simultaneously
+    ratio: f64,
+    /// Routed rate of each demand, zero for demands whose destination is unreachable
+    routed: Vec<InformationRate>,
+    /// Total flow over each link direction
+    link_flows: Vec<((NodeId, NodeId), InformationRate)>,
+}
+
+impl MultiCommodityFlow {
+    pub fn get_ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    pub fn get_routed(&self) -> &[InformationRate] {
+        &self.routed
+    }
+
+    pub fn get_link_flows(&self) -> &[((NodeId, NodeId), InformationRate)] {
+        &self.link_flows
+    }
+
+    /// Returns the sum of the routed rates.
+    pub fn get_throughput(&self) -> InformationRate {
+        self.routed
+            .iter()
+            .fold(InformationRate::new::<bit_per_second>(0.0), |sum, rate| {
+                sum + *rate
+            })
+    }
+}
+
+/// Distance in a Dijkstra search, ordered by the shortest distance first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f64,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Searches the shortest path from the source to the target with the given arc lengths.
+/// Returns the arcs of the path, or `None` if the target is unreachable.
+fn shortest_path(
+    network: &Network,
+    lengths: &[f64],
+    source: usize,
+    target: usize,
+) -> Option<Vec<usize>> {
+    let mut distances = vec![f64::INFINITY; network.nodes.len()];
+    let mut predecessors: Vec<Option<usize>> = vec![None; network.nodes.len()];
+    distances[source] = 0.0;
+    let mut queue = BinaryHeap::from([Candidate {
+        distance: 0.0,
+        node: source,
+    }]);
+    while let Some(Candidate { distance, node }) = queue.pop() {
+        if distance > distances[node] {
+            continue;
+        }
+        if node == target {
+            break;
+        }
+        if !network.may_forward(node, source) {
+            continue;
+        }
+        for &arc in network.outgoing[node].iter() {
+            let head = network.arcs[arc].to;
+            let candidate = distance + lengths[arc];
+            if candidate < distances[head] {
+                distances[head] = candidate;
+                predecessors[head] = Some(arc);
+                queue.push(Candidate {
+                    distance: candidate,
+                    node: head,
+                });
+            }
+        }
+    }
+    if distances[target].is_infinite() {
+        return None;
+    }
+    let mut path = vec![];
+    let mut node = target;
+    while let Some(arc) = predecessors[node] {
+        path.push(arc);
+        node = network.arcs[arc].from;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Approximates the maximum concurrent flow of the demands with the algorithm of Garg and Könemann,
+/// i.e., the largest ratio such that this share of every demand can be routed simultaneously.
+///
+/// The result is feasible and within a factor of about (1 - 3ε) of the optimum.
+/// Demands whose destination is unreachable are not routed and do not limit the ratio.
+pub(crate) fn max_concurrent_flow(
+    network: &Network,
+    demands: &[Demand],
+    epsilon: f64,
+) -> MultiCommodityFlow {
+    assert!(epsilon > 0.0 && epsilon < 1.0);
+    let commodities: Vec<(usize, usize, f64)> = demands
+        .iter()
+        .map(|demand| {
+            (
+                network.indices[&demand.get_source()],
+                network.indices[&demand.get_destination()],
+                demand.get_rate().get::<bit_per_second>(),
+            )
+        })
+        .collect();
+    // route only demands with a positive rate and a positive maximum flow to the destination,
+    // each given with the ratio of its maximum flow to its rate
+    let active: Vec<(usize, f64)> = (0..commodities.len())
+        .filter_map(|j| {
+            let (s, t, rate) = commodities[j];
+            if rate <= 0.0 || s == t {
+                return None;
+            }
+            let value = max_flow(network, network.nodes[s], network.nodes[t])
+                .get_value()
+                .get::<bit_per_second>();
+            (value > 0.0).then_some((j, value / rate))
+        })
+        .collect();
+    let (active, ratios): (Vec<usize>, Vec<f64>) = active.into_iter().unzip();
+
+    let mut flows = vec![0.0; network.arcs.len()];
+    let mut routed = vec![0.0; commodities.len()];
+    if !active.is_empty() && !network.arcs.is_empty() {
+        // scale the demands such that the optimal ratio is between 1 and the number of commodities
+        let upper = ratios.into_iter().fold(f64::INFINITY, f64::min);
+        let scale = upper / active.len() as f64;
+
+        let arcs = network.arcs.len() as f64;
+        let delta = (arcs / (1.0 - epsilon)).powf(-1.0 / epsilon);
+        let mut lengths: Vec<f64> = network
+            .arcs
+            .iter()
+            .map(|arc| delta / arc.capacity)
+            .collect();
+        let volume = |lengths: &[f64]| -> f64 {
+            lengths
+                .iter()
+                .zip(network.arcs.iter())
+                .map(|(length, arc)| length * arc.capacity)
+                .sum()
+        };
+        let mut total = volume(&lengths);
+        'phases: while total < 1.0 {
+            for &j in active.iter() {
+                let (s, t, rate) = commodities[j];
+                let mut remaining = rate * scale;
+                while remaining > 0.0 {
+                    if total >= 1.0 {
+                        break 'phases;
+                    }
+                    // every active demand has a path, this only guards against numerical breakdown
+                    let Some(path) = shortest_path(network, &lengths, s, t) else {
+                        break 'phases;
+                    };
+                    let bottleneck = path
+                        .iter()
+                        .map(|arc| network.arcs[*arc].capacity)
+                        .fold(remaining, f64::min);
+                    for &arc in path.iter() {
+                        flows[arc] += bottleneck;
+                        let capacity = network.arcs[arc].capacity;
+                        let increase = lengths[arc] * epsilon * bottleneck / capacity;
+                        lengths[arc] += increase;
+                        total += increase * capacity;
+                    }
+                    routed[j] += bottleneck;
+                    remaining -= bottleneck;
+                }
+            }
+        }
+        // scale the flows down to the capacities
+        let congestion = flows
+            .iter()
+            .zip(network.arcs.iter())
+            .map(|(flow, arc)| flow / arc.capacity)
+            .fold(0.0, f64::max);
+        if congestion > 0.0 {
+            flows.iter_mut().for_each(|flow| *flow /= congestion);
+            routed.iter_mut().for_each(|rate| *rate /= congestion);
+        }
+    }
+
+    let ratio = active
+        .iter()
+        .map(|j| routed[*j] / commodities[*j].2)
+        .fold(f64::INFINITY, f64::min);
+    let ratio = if ratio.is_finite() { ratio } else { 0.0 };
+    // every demand receives the same share, surplus of individual demands is not counted
+    let routed = (0..commodities.len())
+        .map(|j| {
+            let rate = if active.contains(&j) {
+                commodities[j].2 * ratio
+            } else {
+                0.0
+            };
+            InformationRate::new::<bit_per_second>(rate)
+        })
+        .collect();
+    let link_flows = network
+        .arcs
+        .iter()
+        .zip(flows)
+        .filter(|(_, flow)| *flow > 0.0)
+        .map(|(arc, flow)| {
+            (
+                (network.nodes[arc.from], network.nodes[arc.to]),
+                InformationRate::new::<bit_per_second>(flow),
+            )
+        })
+        .collect();
+    MultiCommodityFlow {
+        ratio,
+        routed,
+        link_flows,
+    }
+}
@@ -0,0 +1,96 @@
+use nyx_space::time::Epoch;
+use rayon::prelude::*;
+use uom::si::{f64::Time, time::millisecond};
+
+use crate::{
+    constellation::node::{Node, NodeId},
+    representations::lla::LLA,
+    satellite::Satellite,
+};
+
+/// A sample of a ground track: the sub-satellite point at an epoch.
+pub(crate) type TrackPoint = (Epoch, LLA);
+
+/// Sub-satellite points of a satellite over time.
+/// The track is split into segments at each antimeridian crossing,
+/// so that each segment can be drawn as a continuous line on a map.
+#[derive(Debug, Clone)]
+pub struct GroundTrack {
+    satellite: NodeId,
+    segments: Vec<Vec<TrackPoint>>,
+}
+
+impl GroundTrack {
+    pub fn get_satellite(&self) -> NodeId {
+        self.satellite
+    }
+
+    pub(crate) fn get_segments(&self) -> &[Vec<TrackPoint>] {
+        &self.segments
+    }
+}
+
+/// Computes the ground tracks of the given satellites, optionally restricted to a single plane,
+/// starting at their current epoch and lasting for the given duration sampled with the given step.
+pub(crate) fn ground_tracks(
+    satellites: &[Satellite],
+    plane: Option<u32>,
+    duration: Time,
+    step: Time,
+) -> Vec<GroundTrack> {
+    assert!(step.get::<millisecond>() > 0.0);
+    assert!(duration.get::<millisecond>() >= 0.0);
+
+    satellites
+        .par_iter()
+        .filter(|sat| plane.is_none_or(|plane| sat.get_plane() == plane))
+        .map(|sat| {
+            let mut sat = sat.clone();
+            let mut points = vec![(sat.get_orbit().dt, sat.get_position_lla())];
+            let mut offset = Time::new::<millisecond>(0.0);
+            while offset < duration {
+                let next = if offset + step > duration {
+                    duration
+                } else {
+                    offset + step
+                };
+                sat.propagate(next - offset);
+                points.push((sat.get_orbit().dt, sat.get_position_lla()));
+                offset = next;
+            }
+            GroundTrack {
+                satellite: sat.get_id(),
+                segments: split_at_antimeridian(points),
+            }
+        })
+        .collect()
+}
+
+/// Splits the track wherever two consecutive points are more than 180° of longitude apart.
+/// The crossing is linearly interpolated and added to both adjacent segments.
+fn split_at_antimeridian(points: Vec<TrackPoint>) -> Vec<Vec<TrackPoint>> {
+    let mut segments = vec![];
+    let mut segment: Vec<TrackPoint> = vec![];
+    for (epoch, lla) in points {
+        if let Some((previous_epoch, previous)) = segment.last().copied() {
+            let delta = lla.get_lon() - previous.get_lon();
+            if delta.abs() > 180.0 {
+                // longitude of the crossing on the side of the previous point, i.e. ±180°
+                let border = 180f64.copysign(previous.get_lon());
+                let unwrapped = lla.get_lon() - 360f64.copysign(delta);
+                let fraction = (border - previous.get_lon()) / (unwrapped - previous.get_lon());
+                let lat = previous.get_lat() + (lla.get_lat() - previous.get_lat()) * fraction;
+                let alt = previous.get_alt() + (lla.get_alt() - previous.get_alt()) * fraction;
+                let crossing = previous_epoch + (epoch - previous_epoch) * fraction;
+                segment.push((crossing, LLA::new(lat, border, alt)));
+                segments.push(segment);
+                segment = vec![(crossing, LLA::new(lat, -border, alt))];
+            }
+        }
+        segment.push((epoch, lla));
+    }
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+    segments
+}
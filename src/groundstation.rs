@@ -1,18 +1,66 @@
-use nyx_space::{od::ui::GroundStation, time::Epoch, Orbit};
+use std::borrow::Cow;
+
+use nyx_space::{
+    cosmic::Frame,
+    linalg::{Matrix3, Vector3},
+    od::ui::GroundStation,
+    time::{Epoch, TimeUnits},
+    Orbit,
+};
 use uom::si::{
-    angle::degree,
-    f64::{Angle, Length},
+    angle::{degree, radian},
+    f64::{Angle, Length, Velocity},
     length::kilometer,
+    velocity::kilometer_per_second,
 };
 
 use crate::{
-    constellation::node::{Node, NodeId, NodePosition, NodeType},
-    helper::{cosm, earth_frame},
+    constellation::node::{Node, NodeId, NodeType},
+    helper::{cosm, earth_fixed_frame},
     networkx_graph::Node as NxNode,
-    representations::lla::LLA,
-    satellite::Satellite,
+    representations::{lla::LLA, look_angles::LookAngles, reference_frame::ReferenceFrame},
+    trajectory::Trajectory,
 };
 
+/// A point fixed on the rotating earth that observes satellites.
+/// Computes the elevation in the topocentric frame (south, east, zenith) of its geodetic position,
+/// which ground stations use for their visibility as well.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Observer {
+    /// Position in the earth-fixed frame in km
+    position: Vector3<f64>,
+    /// Rotation from the earth-fixed frame into the topocentric frame
+    dcm_fixed2topo: Matrix3<f64>,
+}
+
+impl Observer {
+    pub(crate) fn new(lat: Angle, lon: Angle, alt: Length, epoch: Epoch) -> Observer {
+        let orbit = Orbit::from_geodesic(
+            lat.get::<degree>(),
+            lon.get::<degree>(),
+            alt.get::<kilometer>(),
+            epoch,
+            earth_fixed_frame(),
+        );
+        Observer::at(&orbit)
+    }
+
+    /// Creates the observer at the position of the given state in the earth-fixed frame.
+    fn at(state: &Orbit) -> Observer {
+        let dcm_topo2fixed = state.dcm_from_traj_frame(Frame::SEZ).unwrap();
+        Observer {
+            position: state.radius(),
+            dcm_fixed2topo: dcm_topo2fixed.transpose(),
+        }
+    }
+
+    /// Computes the elevation of the given position in the earth-fixed frame in km.
+    pub(crate) fn elevation(&self, position_fixed: &Vector3<f64>) -> Angle {
+        let rho_sez = self.dcm_fixed2topo * (position_fixed - self.position);
+        Angle::new::<radian>(f64::asin(rho_sez[2] / rho_sez.norm()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Groundstation {
     id: NodeId,
@@ -20,6 +68,12 @@ pub(crate) struct Groundstation {
     epoch: Epoch,
     groundstation: GroundStation,
     min_elevation: Angle,
+    /// Population, GDP or any other relative demand of the ground station used by traffic models
+    weight: f64,
+    /// Whether the ground station connects the constellation to the terrestrial network
+    gateway: bool,
+    /// Trajectory of a mobile terminal, fixed ground stations have none
+    trajectory: Option<Trajectory>,
 }
 impl Groundstation {
     pub(crate) fn new(
@@ -36,7 +90,7 @@ impl Groundstation {
             lat.get::<degree>(),
             lon.get::<degree>(),
             alt.get::<kilometer>(),
-            earth_frame(),
+            earth_fixed_frame(),
             cosm(),
         );
 
@@ -45,27 +99,139 @@ impl Groundstation {
             epoch,
             groundstation,
             min_elevation,
+            weight: 1.0,
+            gateway: false,
+            trajectory: None,
         }
     }
 
-    pub fn get_orbit(&self) -> Orbit {
-        self.groundstation.to_orbit(self.epoch)
+    /// Returns this ground station moved to the given position, keeping its ID, name and traffic.
+    /// A mobile terminal stops following its trajectory.
+    pub(crate) fn relocated(&self, lat: Angle, lon: Angle, alt: Length) -> Groundstation {
+        let mut groundstation = Groundstation::new(
+            self.id,
+            self.get_name().to_string(),
+            self.epoch,
+            lat,
+            lon,
+            alt,
+            self.min_elevation,
+        );
+        groundstation.set_traffic(self.weight, self.gateway);
+        groundstation
+    }
+
+    /// Turns the ground station into a mobile terminal following the given trajectory.
+    pub(crate) fn set_trajectory(&mut self, trajectory: Trajectory) {
+        self.trajectory = Some(trajectory);
+        self.update_epoch(self.epoch);
+    }
+
+    /// Returns the nyx ground station at its position at the given epoch.
+    /// Only mobile terminals are located anew, fixed ground stations are borrowed.
+    fn station_at(&self, epoch: Epoch) -> Cow<'_, GroundStation> {
+        match &self.trajectory {
+            None => Cow::Borrowed(&self.groundstation),
+            Some(trajectory) => {
+                let (lat, lon, alt) = trajectory.position(epoch);
+                Cow::Owned(GroundStation::from_point(
+                    self.groundstation.name.clone(),
+                    lat.get::<degree>(),
+                    lon.get::<degree>(),
+                    alt.get::<kilometer>(),
+                    earth_fixed_frame(),
+                    cosm(),
+                ))
+            }
+        }
+    }
+
+    /// Sets the relative demand and the role used by traffic models.
+    /// The weight must be finite and not negative.
+    pub(crate) fn set_traffic(&mut self, weight: f64, gateway: bool) {
+        assert!(weight.is_finite() && weight >= 0.0);
+        self.weight = weight;
+        self.gateway = gateway;
+    }
+
+    pub(crate) fn get_weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub(crate) fn is_gateway(&self) -> bool {
+        self.gateway
+    }
+
+    /// Checks whether the given orbit is above the minimal elevation of this ground station.
+    pub fn is_visible(&self, orbit: &Orbit) -> bool {
+        self.is_above_min_elevation(self.elevation(orbit))
+    }
+
+    pub(crate) fn get_name(&self) -> &str {
+        &self.groundstation.name
+    }
+
+    /// Computes the elevation of the given orbit as seen from this ground station.
+    /// The ground station position is evaluated at the epoch of the orbit.
+    pub(crate) fn elevation(&self, orbit: &Orbit) -> Angle {
+        let station = self.station_at(orbit.dt).to_orbit(orbit.dt);
+        Observer::at(&station).elevation(&ReferenceFrame::ECEF.transform(orbit).radius())
+    }
+
+    /// Computes azimuth, elevation, slant range and range rate of the given orbit as seen from this ground station.
+    /// The ground station position is evaluated at the epoch of the orbit.
+    pub(crate) fn look_angles(&self, orbit: &Orbit) -> LookAngles {
+        // receiver and transmitter in the earth-fixed frame of the ground station
+        let rx = ReferenceFrame::ECEF.transform(orbit);
+        let tx = self.get_orbit_fixed(orbit.dt);
+        // rotate the relative position into the topocentric frame (south, east, zenith)
+        let dcm_topo2fixed = tx.dcm_from_traj_frame(Frame::SEZ).unwrap();
+        let rho = rx.radius() - tx.radius();
+        let rho_dot = rx.velocity() - tx.velocity();
+        let rho_sez = dcm_topo2fixed.transpose() * rho;
+
+        let range = rho.norm();
+        let azimuth = f64::atan2(rho_sez[1], -rho_sez[0]).rem_euclid(std::f64::consts::TAU);
+        let elevation = f64::asin(rho_sez[2] / range);
+        let range_rate = rho.dot(&rho_dot) / range;
+        LookAngles::new(
+            Angle::new::<radian>(azimuth),
+            Angle::new::<radian>(elevation),
+            Length::new::<kilometer>(range),
+            Velocity::new::<kilometer_per_second>(range_rate),
+        )
+    }
+
+    /// Returns the state of the ground station in the earth-fixed frame at the given epoch.
+    /// A fixed ground station does not move relative to the rotating earth, hence its velocity is zero.
+    /// The velocity of a mobile terminal is the mean velocity over the following second.
+    fn get_orbit_fixed(&self, epoch: Epoch) -> Orbit {
+        let orbit = self.station_at(epoch).to_orbit(epoch);
+        let velocity = match self.trajectory {
+            None => Vector3::zeros(),
+            Some(_) => {
+                let later = epoch + 1.seconds();
+                self.station_at(later).to_orbit(later).radius() - orbit.radius()
+            }
+        };
+        orbit.with_velocity(&velocity)
+    }
+
+    pub(crate) fn get_min_elevation(&self) -> Angle {
+        self.min_elevation
     }
 
-    pub fn is_visible(&self, sat: &Satellite) -> bool {
-        let (elevation, _, _) = self.groundstation.elevation_of(&sat.get_orbit());
-        let elevation: Angle = Angle::new::<degree>(elevation);
-        // println!(
-        //     "Elevation between GS({}) and Sat({}) is {}",
-        //     self.get_id(),
-        //     sat.get_id(),
-        //     elevation.get::<degree>()
-        // );
+    /// Checks whether the given elevation satisfies the minimal elevation of this ground station.
+    pub(crate) fn is_above_min_elevation(&self, elevation: Angle) -> bool {
         elevation >= self.min_elevation
     }
 
+    /// Sets the epoch of the ground station and moves a mobile terminal along its trajectory.
     pub(crate) fn update_epoch(&mut self, new_epoch: Epoch) {
-        self.epoch = new_epoch
+        self.epoch = new_epoch;
+        if self.trajectory.is_some() {
+            self.groundstation = self.station_at(new_epoch).into_owned();
+        }
     }
 }
 
@@ -75,30 +241,21 @@ impl Node for Groundstation {
     }
 
     fn get_node_type(&self) -> NodeType {
-        NodeType::Groundstation
-    }
-
-    fn get_position_ecef(&self) -> NodePosition {
-        let orbit = self.get_orbit();
-        let x: Length = Length::new::<kilometer>(orbit.x);
-        let y: Length = Length::new::<kilometer>(orbit.y);
-        let z: Length = Length::new::<kilometer>(orbit.z);
-        NodePosition::new(x, y, z)
-    }
-
-    fn get_x(&self) -> Length {
-        let orbit = self.get_orbit();
-        Length::new::<kilometer>(orbit.x)
+        match self.trajectory {
+            None => NodeType::Groundstation,
+            Some(_) => NodeType::Terminal,
+        }
     }
 
-    fn get_y(&self) -> Length {
-        let orbit = self.get_orbit();
-        Length::new::<kilometer>(orbit.y)
+    fn get_orbit(&self) -> Orbit {
+        ReferenceFrame::ECI.transform(&self.get_orbit_fixed(self.epoch))
     }
 
-    fn get_z(&self) -> Length {
+    fn get_acceleration(&self) -> Vector3<f64> {
+        // centripetal acceleration of the rotating earth
         let orbit = self.get_orbit();
-        Length::new::<kilometer>(orbit.z)
+        let omega = Vector3::new(0.0, 0.0, earth_fixed_frame().angular_velocity());
+        omega.cross(&orbit.velocity())
     }
 
     fn get_position_lla(&self) -> LLA {
@@ -113,7 +270,7 @@ impl Node for Groundstation {
     }
 
     fn get_lon(&self) -> Angle {
-        Angle::new::<degree>(self.groundstation.latitude)
+        Angle::new::<degree>(self.groundstation.longitude)
     }
 
     fn get_height(&self) -> Length {
@@ -125,6 +282,8 @@ impl From<Groundstation> for NxNode {
     fn from(value: Groundstation) -> Self {
         NxNode {
             id: value.get_id().into(),
+            node_type: value.get_node_type().into(),
+            failed: false,
         }
     }
 }
@@ -1,8 +1,18 @@
 use std::sync::Arc;
 
-use nyx_space::cosmic::{Cosm, Frame};
+use nyx_space::{
+    cosmic::{Cosm, Frame},
+    time::{Duration, Unit},
+};
 use once_cell::sync::Lazy;
-use uom::si::{angle::degree, f64::Angle};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Time},
+    time::millisecond,
+};
+
+/// Speed of light in km/s.
+pub(crate) const SPEED_OF_LIGHT: f64 = 299_792.458;
 
 // Load the NASA NAIF DE438 planetary ephemeris.
 static COSM: Lazy<Arc<Cosm>> = Lazy::new(Cosm::de438);
@@ -24,6 +34,21 @@ pub(crate) fn earth_frame() -> Frame {
     COSM.frame("EME2000")
 }
 
+pub(crate) fn earth_fixed_frame() -> Frame {
+    // Grab the body-fixed frame rotating with the earth
+    COSM.frame("IAU Earth")
+}
+
 pub(crate) fn cosm() -> Arc<Cosm> {
     COSM.to_owned()
 }
+
+/// Converts a uom time into a hifitime duration with millisecond resolution.
+pub(crate) fn duration(time: Time) -> Duration {
+    Duration::from_f64(time.get::<millisecond>(), Unit::Millisecond)
+}
+
+/// Converts a hifitime duration into a uom time.
+pub(crate) fn time(duration: Duration) -> Time {
+    Time::new::<millisecond>(duration.in_unit(Unit::Millisecond))
+}
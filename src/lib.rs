@@ -1,26 +1,65 @@
 use constellation::{Constellation, ConstellationType};
+use coverage::CoverageStatistics;
+use events::EventStream;
+use itertools::Itertools;
+use link_budget::LinkBudget;
+use link_load::LoadReport;
+use representations::{reference_frame::ReferenceFrame, undirected_link::UndirectedLink};
 
 use networkx_graph::Graph as NxGraph;
 use nyx_space::time::Epoch;
+use satellite::OrbitShape;
+use simulator::{Arrivals, Flow, Routing, SimulationConfig};
+use std::{collections::HashMap, path::Path};
+use traffic::{Demand, TrafficModel};
+
 use pyo3::{
+    exceptions::{PyIOError, PyValueError},
     prelude::*,
-    types::{PyDict, PyTuple},
+    types::{PyDict, PyList, PyTuple},
 };
 
 use uom::si::{
     angle::degree,
-    f64::{Angle, Length},
+    f64::{Angle, Information, InformationRate, Length, Time},
+    information::byte,
+    information_rate::{byte_per_second, megabit_per_second},
     length::kilometer,
+    time::millisecond,
+    velocity::kilometer_per_second,
 };
 
+mod cgr;
 mod constellation;
+mod contact_plan;
+mod coverage;
+mod events;
+mod failures;
+mod flow;
+mod ground_track;
 mod groundstation;
 mod helper;
+mod link_budget;
+mod link_load;
 mod networkx_graph;
+mod rain;
+mod relay;
 mod representations;
+mod robustness;
 mod satellite;
+mod simulator;
+mod spatial_index;
+mod temporal_graph;
+mod traffic;
+mod trajectory;
+mod visibility;
 
+/// Creates a Walker constellation whose satellites share the orbit shape: circular at the altitude (km) by default,
+/// otherwise eccentric with the perigee at the altitude and the argument of perigee (°).
+/// The satellites of a plane are spaced equally in time along their orbit.
 #[pyfunction]
+#[pyo3(signature = (satellites, planes, ipc, altitude, inclination, min_elevation, constellation_type, eccentricity = 0.0, argument_of_perigee = 0.0))]
+#[allow(clippy::too_many_arguments)]
 fn create_constellation(
     satellites: u32,
     planes: u32,
@@ -29,8 +68,18 @@ fn create_constellation(
     inclination: f64,
     min_elevation: f64,
     constellation_type: ConstellationType,
+    eccentricity: f64,
+    argument_of_perigee: f64,
 ) -> PyResult<Constellation> {
+    if !(0.0..1.0).contains(&eccentricity) {
+        return Err(PyValueError::new_err("eccentricity must be in [0, 1)"));
+    }
     let altitude: Length = Length::new::<kilometer>(altitude as f64);
+    let shape = OrbitShape::eccentric(
+        altitude,
+        eccentricity,
+        Angle::new::<degree>(argument_of_perigee),
+    );
     let inclination: Angle = Angle::new::<degree>(inclination);
     let min_elevation: Angle = Angle::new::<degree>(min_elevation);
     let epoch = Epoch::now().unwrap();
@@ -39,7 +88,7 @@ fn create_constellation(
         satellites,
         planes,
         ipc,
-        altitude,
+        shape,
         inclination,
         epoch,
         min_elevation,
@@ -52,10 +101,14 @@ fn extract_graph<'a>(py: Python<'a>, constellation: &'a Constellation) -> PyResu
     Ok(internal_graph.to_object(py).into_ref(py))
 }
 
+/// Returns the position of each node in the given frame (ECI by default).
+/// Each entry records the frame of its coordinates.
 #[pyfunction]
+#[pyo3(signature = (constellation, frame = ReferenceFrame::ECI))]
 fn extract_positions_3d<'a>(
     py: Python<'a>,
     constellation: &'a Constellation,
+    frame: ReferenceFrame,
 ) -> PyResult<&'a PyDict> {
     let dict = PyDict::new(py);
     constellation
@@ -65,14 +118,16 @@ fn extract_positions_3d<'a>(
             (
                 node.get_id(),
                 node.get_node_type(),
-                node.get_position_ecef(),
+                node.get_position(frame),
             )
         })
         .for_each(|(id, typ, pos)| {
             let id: u32 = id.into();
             let typ = char::from(typ).to_object(py);
             let xyz = PyTuple::new(py, vec![pos.get_x(), pos.get_y(), pos.get_z()]).to_object(py);
-            dict.set_item(id, PyTuple::new(py, vec![typ, xyz])).unwrap();
+            let frame = <&str>::from(pos.get_frame()).to_object(py);
+            dict.set_item(id, PyTuple::new(py, vec![typ, xyz, frame]))
+                .unwrap();
         });
     Ok(dict)
 }
@@ -97,14 +152,797 @@ fn project_3d_positions<'a>(
     Ok(dict)
 }
 
+/// Samples the links over the given duration (ms) with the given step (ms) into a time-expanded networkx DiGraph.
+/// Nodes are (node ID, layer) tuples, edges are links within a layer (`kind="link"`)
+/// and storage edges from each node to itself in the next layer (`kind="storage"`).
+#[pyfunction]
+fn extract_temporal_graph<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    duration: i32,
+    step: i32,
+) -> PyResult<&'a PyAny> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let graph = constellation.temporal_graph(duration, step);
+    Ok(graph.to_object(py).into_ref(py))
+}
+
+/// Samples the links over the given duration (ms) with the given step (ms) and writes the time-expanded graph
+/// as compact JSON: `{"nodes": [...], "times": [...], "links": [[layer, source, target, weight], ...]}`.
+#[pyfunction]
+fn write_temporal_graph(
+    constellation: &Constellation,
+    duration: i32,
+    step: i32,
+    path: &str,
+) -> PyResult<()> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    constellation
+        .temporal_graph(duration, step)
+        .write(Path::new(path))
+        .map_err(|err| PyIOError::new_err(err.to_string()))
+}
+
+/// Derives the unidirectional contacts of all links over the given duration (ms) sampled with the given step (ms).
+/// Times and one-way light times are given in ms relative to the current epoch, rates in bytes/s.
+/// Links without link budget use the given rate.
+#[pyfunction]
+#[pyo3(signature = (constellation, duration, step, rate = 1e6))]
+fn extract_contacts<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    duration: i32,
+    step: i32,
+    rate: f64,
+) -> PyResult<&'a PyList> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let rate: InformationRate = InformationRate::new::<byte_per_second>(rate);
+    let list = PyList::empty(py);
+    for contact in constellation.contacts(duration, step, rate) {
+        let dict = PyDict::new(py);
+        let from: u32 = contact.get_from().into();
+        let to: u32 = contact.get_to().into();
+        dict.set_item("from", from)?;
+        dict.set_item("to", to)?;
+        dict.set_item("start", contact.get_start().get::<millisecond>())?;
+        dict.set_item("end", contact.get_end().get::<millisecond>())?;
+        dict.set_item("rate", contact.get_rate().get::<byte_per_second>())?;
+        dict.set_item("owlt", contact.get_owlt().get::<millisecond>())?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// Writes an ION contact plan (`a contact` and `a range` lines) of all links over the given duration (ms)
+/// sampled with the given step (ms). Times are given in seconds relative to the current epoch,
+/// node numbers are the node IDs plus one. Links without link budget use the given rate in bytes/s.
+#[pyfunction]
+#[pyo3(signature = (constellation, duration, step, path, rate = 1e6))]
+fn write_contact_plan(
+    constellation: &Constellation,
+    duration: i32,
+    step: i32,
+    path: &str,
+    rate: f64,
+) -> PyResult<()> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let rate: InformationRate = InformationRate::new::<byte_per_second>(rate);
+    let contacts = constellation.contacts(duration, step, rate);
+    contact_plan::write_contact_plan(Path::new(path), &contacts)
+        .map_err(|err| PyIOError::new_err(err.to_string()))
+}
+
+/// Searches the route of a bundle with the given size (bytes) from the source to the destination node
+/// with the earliest delivery time by contact graph routing, if the bundle is available at the given start (ms).
+/// The contacts are derived from the links over the given duration (ms) sampled with the given step (ms),
+/// links without link budget use the given rate in bytes/s. All times are given in ms relative to the current epoch.
+/// Returns `None` if the destination is not reachable within the duration,
+/// a bundle for its own source is delivered at the start without hops.
+#[pyfunction]
+#[pyo3(signature = (constellation, source, destination, start, size, duration, step, rate = 1e6))]
+#[allow(clippy::too_many_arguments)]
+fn find_route<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    source: u32,
+    destination: u32,
+    start: i32,
+    size: f64,
+    duration: i32,
+    step: i32,
+    rate: f64,
+) -> PyResult<Option<&'a PyDict>> {
+    if !constellation.contains_node(source.into())
+        || !constellation.contains_node(destination.into())
+    {
+        return Err(PyValueError::new_err("unknown source or destination node"));
+    }
+    if !(size.is_finite() && size > 0.0) {
+        return Err(PyValueError::new_err("bundle size must be positive"));
+    }
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let start: Time = Time::new::<millisecond>(start as f64);
+    let size: Information = Information::new::<byte>(size);
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let rate: InformationRate = InformationRate::new::<byte_per_second>(rate);
+    let contacts = constellation.contacts(duration, step, rate);
+    let Some(route) = cgr::best_route(&contacts, source.into(), destination.into(), start, size)
+    else {
+        return Ok(None);
+    };
+
+    let hops = PyList::empty(py);
+    for hop in route.get_hops() {
+        let contact = hop.get_contact();
+        let dict = PyDict::new(py);
+        let from: u32 = contact.get_from().into();
+        let to: u32 = contact.get_to().into();
+        dict.set_item("from", from)?;
+        dict.set_item("to", to)?;
+        dict.set_item("contact_start", contact.get_start().get::<millisecond>())?;
+        dict.set_item("contact_end", contact.get_end().get::<millisecond>())?;
+        dict.set_item(
+            "transmission_start",
+            hop.get_transmission_start().get::<millisecond>(),
+        )?;
+        dict.set_item("arrival", hop.get_arrival().get::<millisecond>())?;
+        dict.set_item("consumption", hop.get_consumption())?;
+        hops.append(dict)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item(
+        "delivery_time",
+        route.get_delivery_time().get::<millisecond>(),
+    )?;
+    dict.set_item("hops", hops)?;
+    Ok(Some(dict))
+}
+
+/// Simulates flows of packets between nodes over the given duration (ms) with a packet-level discrete-event simulation.
+/// Each flow is given as (source, destination, rate in Mbit/s, packet size in bytes).
+/// The topology is updated every `topology_step` ms and each link direction queues up to `queue_size` packets.
+/// Packets are routed on shortest paths recomputed every `routing_interval` ms (default: `topology_step`),
+/// or by the static next hops `{(node, destination): next_hop}` if given.
+/// Links without link budget use the given rate in Mbit/s. The results only depend on the seed.
+#[pyfunction]
+#[pyo3(signature = (
+    constellation,
+    flows,
+    duration,
+    topology_step,
+    routing_interval = None,
+    static_routes = None,
+    queue_size = 100,
+    rate = 100.0,
+    seed = 0,
+    poisson = false
+))]
+#[allow(clippy::too_many_arguments)]
+fn simulate<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    flows: Vec<(u32, u32, f64, f64)>,
+    duration: i32,
+    topology_step: i32,
+    routing_interval: Option<i32>,
+    static_routes: Option<HashMap<(u32, u32), u32>>,
+    queue_size: usize,
+    rate: f64,
+    seed: u64,
+    poisson: bool,
+) -> PyResult<&'a PyList> {
+    if flows.iter().any(|(source, destination, _, _)| {
+        !constellation.contains_node((*source).into())
+            || !constellation.contains_node((*destination).into())
+    }) {
+        return Err(PyValueError::new_err("unknown source or destination node"));
+    }
+    if !flows
+        .iter()
+        .all(|(_, _, rate, size)| rate.is_finite() && *rate > 0.0 && *size > 0.0)
+    {
+        return Err(PyValueError::new_err(
+            "flow rate and packet size must be positive",
+        ));
+    }
+    if topology_step <= 0 {
+        return Err(PyValueError::new_err("topology_step must be positive"));
+    }
+    if routing_interval.is_some_and(|interval| interval <= 0) {
+        return Err(PyValueError::new_err("routing_interval must be positive"));
+    }
+    if !(rate.is_finite() && rate > 0.0) {
+        return Err(PyValueError::new_err("rate must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let arrivals = if poisson {
+        Arrivals::Poisson
+    } else {
+        Arrivals::Constant
+    };
+    let flows = flows
+        .iter()
+        .map(|(source, destination, rate, size)| {
+            Flow::new(
+                (*source).into(),
+                (*destination).into(),
+                InformationRate::new::<megabit_per_second>(*rate),
+                Information::new::<byte>(*size),
+                arrivals,
+            )
+        })
+        .collect_vec();
+    let routing = match static_routes {
+        Some(routes) => Routing::Static(
+            routes
+                .into_iter()
+                .map(|((node, destination), next)| ((node.into(), destination.into()), next.into()))
+                .collect(),
+        ),
+        None => Routing::ShortestPath {
+            interval: Time::new::<millisecond>(routing_interval.unwrap_or(topology_step) as f64),
+        },
+    };
+    let config = SimulationConfig::new(
+        Time::new::<millisecond>(duration as f64),
+        Time::new::<millisecond>(topology_step as f64),
+        routing,
+        queue_size,
+        InformationRate::new::<megabit_per_second>(rate),
+        seed,
+    );
+
+    let list = PyList::empty(py);
+    let latency = |latency: Option<Time>| latency.map(|latency| latency.get::<millisecond>());
+    for (flow, statistics) in flows.iter().zip(constellation.simulate(&flows, &config)) {
+        let dict = PyDict::new(py);
+        let source: u32 = flow.get_source().into();
+        let destination: u32 = flow.get_destination().into();
+        dict.set_item("source", source)?;
+        dict.set_item("destination", destination)?;
+        dict.set_item("sent", statistics.get_sent())?;
+        dict.set_item("received", statistics.get_received())?;
+        dict.set_item("dropped", statistics.get_dropped())?;
+        dict.set_item("loss", statistics.get_loss())?;
+        dict.set_item("mean_latency", latency(statistics.get_mean_latency()))?;
+        dict.set_item("min_latency", latency(statistics.get_min_latency()))?;
+        dict.set_item("max_latency", latency(statistics.get_max_latency()))?;
+        dict.set_item(
+            "throughput",
+            statistics.get_throughput().get::<megabit_per_second>(),
+        )?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// Generates a traffic matrix between the ground stations as (source, destination, rate in Mbit/s) tuples,
+/// scaled to the given total rate in Mbit/s. Models:
+/// - `uniform`: equal demand between all pairs
+/// - `gravity`: demand proportional to the product of the station weights,
+///   divided by their great-circle distance raised to `distance_exponent`
+/// - `gateway`: demand from each terminal to its nearest gateway proportional to the terminal weight
+#[pyfunction]
+#[pyo3(signature = (constellation, model = "gravity", total = 1000.0, distance_exponent = 1.0))]
+fn extract_traffic_matrix(
+    constellation: &Constellation,
+    model: &str,
+    total: f64,
+    distance_exponent: f64,
+) -> PyResult<Vec<(u32, u32, f64)>> {
+    let model = match model {
+        "uniform" => TrafficModel::Uniform,
+        "gravity" => TrafficModel::Gravity { distance_exponent },
+        "gateway" => TrafficModel::Gateway,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown traffic model {}",
+                model
+            )))
+        }
+    };
+    let total: InformationRate = InformationRate::new::<megabit_per_second>(total);
+    Ok(constellation
+        .traffic_matrix(model, total)
+        .into_iter()
+        .map(|demand| {
+            (
+                demand.get_source().into(),
+                demand.get_destination().into(),
+                demand.get_rate().get::<megabit_per_second>(),
+            )
+        })
+        .collect())
+}
+
+/// Computes the maximum flow in Mbit/s from the source to the destination node over the current links.
+/// Links without link budget offer the given rate in Mbit/s in each direction, ground stations do not relay traffic.
+/// Returns a dict with the flow `value`, the directed links of the minimum `cut`
+/// and the nodes on the `source_side` of the cut.
+#[pyfunction]
+#[pyo3(signature = (constellation, source, destination, rate = 100.0))]
+fn max_flow<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    source: u32,
+    destination: u32,
+    rate: f64,
+) -> PyResult<&'a PyDict> {
+    if !constellation.contains_node(source.into())
+        || !constellation.contains_node(destination.into())
+    {
+        return Err(PyValueError::new_err("unknown source or destination node"));
+    }
+    let flow = constellation.max_flow(
+        source.into(),
+        destination.into(),
+        InformationRate::new::<megabit_per_second>(rate),
+    );
+    let dict = PyDict::new(py);
+    dict.set_item("value", flow.get_value().get::<megabit_per_second>())?;
+    let cut: Vec<(u32, u32)> = flow
+        .get_cut()
+        .iter()
+        .map(|(from, to)| ((*from).into(), (*to).into()))
+        .collect();
+    dict.set_item("cut", cut)?;
+    let source_side: Vec<u32> = flow
+        .get_source_side()
+        .iter()
+        .map(|node| (*node).into())
+        .collect();
+    dict.set_item("source_side", source_side)?;
+    Ok(dict)
+}
+
+/// Approximates the maximum concurrent flow of the given (source, destination, rate in Mbit/s) demands
+/// over the current links, e.g., a traffic matrix. Links without link budget offer the given rate in Mbit/s
+/// in each direction, ground stations do not relay traffic.
+/// Returns a dict with the `ratio` of every demand that can be routed simultaneously, the total `throughput`,
+/// the routed `flows` as (source, destination, Mbit/s) and the `link_flows` as (from, to, Mbit/s).
+#[pyfunction]
+#[pyo3(signature = (constellation, demands, rate = 100.0, epsilon = 0.1))]
+fn multi_commodity_flow<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    demands: Vec<(u32, u32, f64)>,
+    rate: f64,
+    epsilon: f64,
+) -> PyResult<&'a PyDict> {
+    if demands.iter().any(|(source, destination, _)| {
+        !constellation.contains_node((*source).into())
+            || !constellation.contains_node((*destination).into())
+    }) {
+        return Err(PyValueError::new_err("unknown source or destination node"));
+    }
+    if !(epsilon > 0.0 && epsilon < 1.0) {
+        return Err(PyValueError::new_err("epsilon must be between 0 and 1"));
+    }
+    let demands = demands
+        .iter()
+        .map(|(source, destination, rate)| {
+            Demand::new(
+                (*source).into(),
+                (*destination).into(),
+                InformationRate::new::<megabit_per_second>(*rate),
+            )
+        })
+        .collect_vec();
+    let flow = constellation.max_concurrent_flow(
+        &demands,
+        InformationRate::new::<megabit_per_second>(rate),
+        epsilon,
+    );
+    let dict = PyDict::new(py);
+    dict.set_item("ratio", flow.get_ratio())?;
+    dict.set_item(
+        "throughput",
+        flow.get_throughput().get::<megabit_per_second>(),
+    )?;
+    let flows: Vec<(u32, u32, f64)> = demands
+        .iter()
+        .zip(flow.get_routed())
+        .map(|(demand, routed)| {
+            (
+                demand.get_source().into(),
+                demand.get_destination().into(),
+                routed.get::<megabit_per_second>(),
+            )
+        })
+        .collect();
+    dict.set_item("flows", flows)?;
+    let link_flows: Vec<(u32, u32, f64)> = flow
+        .get_link_flows()
+        .iter()
+        .map(|((from, to), rate)| {
+            (
+                (*from).into(),
+                (*to).into(),
+                rate.get::<megabit_per_second>(),
+            )
+        })
+        .collect();
+    dict.set_item("link_flows", link_flows)?;
+    Ok(dict)
+}
+
+/// Routes the given (source, destination, rate in Mbit/s) demands, e.g., a traffic matrix,
+/// along the shortest paths at each step in ms over the duration in ms and reports the link loads.
+#[pyfunction]
+fn route_traffic(
+    constellation: &Constellation,
+    demands: Vec<(u32, u32, f64)>,
+    duration: i32,
+    step: i32,
+) -> PyResult<LoadReport> {
+    if demands.iter().any(|(source, destination, _)| {
+        !constellation.contains_node((*source).into())
+            || !constellation.contains_node((*destination).into())
+    }) {
+        return Err(PyValueError::new_err("unknown source or destination node"));
+    }
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let demands = demands
+        .iter()
+        .map(|(source, destination, rate)| {
+            Demand::new(
+                (*source).into(),
+                (*destination).into(),
+                InformationRate::new::<megabit_per_second>(*rate),
+            )
+        })
+        .collect_vec();
+    Ok(constellation.link_loads(
+        &demands,
+        Time::new::<millisecond>(duration as f64),
+        Time::new::<millisecond>(step as f64),
+    ))
+}
+
+/// Returns the links that were added and removed by the last propagation of the constellation
+/// and by the changes of the constellation since then, e.g., added ground stations or failures.
+/// Each link is given as tuple of the IDs of its nodes.
+#[pyfunction]
+fn extract_link_diff<'a>(py: Python<'a>, constellation: &'a Constellation) -> PyResult<&'a PyDict> {
+    let diff = constellation.get_last_diff();
+    let to_list = |links: &[UndirectedLink]| {
+        links
+            .iter()
+            .map(|link| {
+                let first: u32 = link.get_first().into();
+                let second: u32 = link.get_second().into();
+                (first, second)
+            })
+            .collect_vec()
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("added", to_list(diff.get_added()))?;
+    dict.set_item("removed", to_list(diff.get_removed()))?;
+    Ok(dict)
+}
+
+/// Computes the contact windows between all ground stations and satellites.
+/// All times are given in milliseconds relative to the current epoch of the constellation.
+#[pyfunction]
+fn extract_visibility_windows<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    duration: i32,
+    step: i32,
+) -> PyResult<&'a PyList> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let start = constellation.get_epoch();
+    let offset = |epoch| helper::time(epoch - start).get::<millisecond>();
+    let list = PyList::empty(py);
+    for window in constellation.visibility_windows(duration, step) {
+        let dict = PyDict::new(py);
+        let gs: u32 = window.get_groundstation().into();
+        let sat: u32 = window.get_satellite().into();
+        dict.set_item("groundstation", gs)?;
+        dict.set_item("satellite", sat)?;
+        dict.set_item("aos", offset(window.get_aos()))?;
+        dict.set_item("los", offset(window.get_los()))?;
+        dict.set_item("max_elevation", window.get_max_elevation().get::<degree>())?;
+        dict.set_item(
+            "max_elevation_time",
+            offset(window.get_max_elevation_epoch()),
+        )?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// Returns azimuth (°), elevation (°), slant range (km) and range rate (km/s) of each established GSL.
+#[pyfunction]
+fn extract_gsl_look_angles<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+) -> PyResult<&'a PyList> {
+    let list = PyList::empty(py);
+    for link in constellation.get_gsls() {
+        let Some(look) = link.get_look_angles() else {
+            continue;
+        };
+        let dict = PyDict::new(py);
+        let gs: u32 = link.get_first().into();
+        let sat: u32 = link.get_second().into();
+        dict.set_item("groundstation", gs)?;
+        dict.set_item("satellite", sat)?;
+        dict.set_item("azimuth", look.get_azimuth().get::<degree>())?;
+        dict.set_item("elevation", look.get_elevation().get::<degree>())?;
+        dict.set_item("range", look.get_range().get::<kilometer>())?;
+        dict.set_item(
+            "range_rate",
+            look.get_range_rate().get::<kilometer_per_second>(),
+        )?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// Computes the sub-satellite points of each satellite (or only those of the given plane).
+/// Returns for each satellite a list of segments split at the antimeridian,
+/// each segment consisting of (time in ms relative to the current epoch, lat, lon, alt) tuples.
+#[pyfunction]
+#[pyo3(signature = (constellation, duration, step, plane = None))]
+fn extract_ground_tracks<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    duration: i32,
+    step: i32,
+    plane: Option<u32>,
+) -> PyResult<&'a PyDict> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let start = constellation.get_epoch();
+    let dict = PyDict::new(py);
+    for track in constellation.ground_tracks(plane, duration, step) {
+        let id: u32 = track.get_satellite().into();
+        let segments = PyList::empty(py);
+        for segment in track.get_segments() {
+            let points = segment
+                .iter()
+                .map(|(epoch, lla)| {
+                    let time = helper::time(*epoch - start).get::<millisecond>();
+                    (time, lla.get_lat(), lla.get_lon(), lla.get_alt())
+                })
+                .collect::<Vec<_>>();
+            segments.append(points)?;
+        }
+        dict.set_item(id, segments)?;
+    }
+    Ok(dict)
+}
+
+/// Computes the coverage of points on the earth surface over the given duration (ms) sampled with the given step (ms).
+/// With `grid`, the points are all combinations of the latitudes and longitudes and each statistic is a
+/// `len(latitudes) x len(longitudes)` array. Otherwise, latitudes and longitudes are zipped into single points.
+/// Statistics: mean/min/max number of visible satellites, coverage (% of time), max gap (ms) and revisit time (ms).
+#[pyfunction]
+#[pyo3(signature = (constellation, latitudes, longitudes, duration, step, min_elevation = None, grid = true))]
+#[allow(clippy::too_many_arguments)]
+fn analyze_coverage<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    latitudes: Vec<f64>,
+    longitudes: Vec<f64>,
+    duration: i32,
+    step: i32,
+    min_elevation: Option<f64>,
+    grid: bool,
+) -> PyResult<&'a PyDict> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    if !grid && latitudes.len() != longitudes.len() {
+        return Err(PyValueError::new_err(
+            "latitudes and longitudes must have the same length",
+        ));
+    }
+    let points: Vec<(Angle, Angle)> = if grid {
+        latitudes
+            .iter()
+            .cartesian_product(longitudes.iter())
+            .map(|(lat, lon)| (Angle::new::<degree>(*lat), Angle::new::<degree>(*lon)))
+            .collect()
+    } else {
+        latitudes
+            .iter()
+            .zip(longitudes.iter())
+            .map(|(lat, lon)| (Angle::new::<degree>(*lat), Angle::new::<degree>(*lon)))
+            .collect()
+    };
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let min_elevation: Option<Angle> = min_elevation.map(Angle::new::<degree>);
+    let statistics = constellation.coverage(&points, min_elevation, duration, step);
+
+    // reshape the flat statistics into rows of the grid
+    let shape = |values: Vec<f64>| -> PyObject {
+        if grid && !longitudes.is_empty() {
+            values
+                .chunks(longitudes.len())
+                .map(|row| row.to_vec())
+                .collect::<Vec<_>>()
+                .to_object(py)
+        } else {
+            values.to_object(py)
+        }
+    };
+    let extract = |f: fn(&CoverageStatistics) -> f64| statistics.iter().map(f).collect_vec();
+    let dict = PyDict::new(py);
+    dict.set_item("mean_visible", shape(extract(|s| s.get_mean_visible())))?;
+    dict.set_item(
+        "min_visible",
+        shape(extract(|s| s.get_min_visible() as f64)),
+    )?;
+    dict.set_item(
+        "max_visible",
+        shape(extract(|s| s.get_max_visible() as f64)),
+    )?;
+    dict.set_item("coverage", shape(extract(|s| s.get_coverage() * 100.0)))?;
+    dict.set_item(
+        "max_gap",
+        shape(extract(|s| s.get_max_gap().get::<millisecond>())),
+    )?;
+    dict.set_item(
+        "revisit_time",
+        shape(extract(|s| s.get_revisit_time().get::<millisecond>())),
+    )?;
+    Ok(dict)
+}
+
+/// Computes graph-level robustness metrics of the topology at each step in ms over the duration in ms.
+/// Failed nodes are not part of the topology. Each entry holds the number of connected `components`,
+/// the `hop_diameter` and the `delay_diameter` in ms, the `mean_hops` and `mean_delay` in ms
+/// between connected ground stations, the `algebraic_connectivity`, the `articulation_points`
+/// and the `top` nodes and links with the highest normalized betweenness.
+#[pyfunction]
+#[pyo3(signature = (constellation, duration, step, top = 10))]
+fn analyze_robustness<'a>(
+    py: Python<'a>,
+    constellation: &'a Constellation,
+    duration: i32,
+    step: i32,
+    top: usize,
+) -> PyResult<&'a PyList> {
+    if step <= 0 {
+        return Err(PyValueError::new_err("step must be positive"));
+    }
+    if duration < 0 {
+        return Err(PyValueError::new_err("duration must not be negative"));
+    }
+    let duration: Time = Time::new::<millisecond>(duration as f64);
+    let step: Time = Time::new::<millisecond>(step as f64);
+    let list = PyList::empty(py);
+    for metrics in constellation.robustness(duration, step, top) {
+        let dict = PyDict::new(py);
+        dict.set_item("time", metrics.get_time().get::<millisecond>())?;
+        dict.set_item("components", metrics.get_components())?;
+        dict.set_item("hop_diameter", metrics.get_hop_diameter())?;
+        dict.set_item(
+            "delay_diameter",
+            metrics.get_delay_diameter().get::<millisecond>(),
+        )?;
+        dict.set_item("mean_hops", metrics.get_mean_hops())?;
+        dict.set_item(
+            "mean_delay",
+            metrics
+                .get_mean_delay()
+                .map(|delay| delay.get::<millisecond>()),
+        )?;
+        dict.set_item(
+            "algebraic_connectivity",
+            metrics.get_algebraic_connectivity(),
+        )?;
+        let articulation_points: Vec<u32> = metrics
+            .get_articulation_points()
+            .iter()
+            .map(|node| (*node).into())
+            .collect();
+        dict.set_item("articulation_points", articulation_points)?;
+        let node_betweenness: Vec<(u32, f64)> = metrics
+            .get_node_betweenness()
+            .iter()
+            .map(|(node, value)| ((*node).into(), *value))
+            .collect();
+        dict.set_item("node_betweenness", node_betweenness)?;
+        let edge_betweenness: Vec<(u32, u32, f64)> = metrics
+            .get_edge_betweenness()
+            .iter()
+            .map(|((u, v), value)| ((*u).into(), (*v).into(), *value))
+            .collect();
+        dict.set_item("edge_betweenness", edge_betweenness)?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn cstl_ntwkx(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<ConstellationType>()?;
     m.add_class::<Constellation>()?;
+    m.add_class::<LinkBudget>()?;
+    m.add_class::<EventStream>()?;
+    m.add_class::<LoadReport>()?;
+    m.add_class::<ReferenceFrame>()?;
     m.add_function(wrap_pyfunction!(create_constellation, m)?)?;
     m.add_function(wrap_pyfunction!(extract_graph, m)?)?;
     m.add_function(wrap_pyfunction!(extract_positions_3d, m)?)?;
     m.add_function(wrap_pyfunction!(project_3d_positions, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_link_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_contacts, m)?)?;
+    m.add_function(wrap_pyfunction!(write_contact_plan, m)?)?;
+    m.add_function(wrap_pyfunction!(find_route, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_traffic_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(max_flow, m)?)?;
+    m.add_function(wrap_pyfunction!(multi_commodity_flow, m)?)?;
+    m.add_function(wrap_pyfunction!(route_traffic, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_temporal_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(write_temporal_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_visibility_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_gsl_look_angles, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_ground_tracks, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_coverage, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_robustness, m)?)?;
     Ok(())
 }
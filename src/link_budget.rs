@@ -0,0 +1,222 @@
+// pyo3 0.20 expands python constructors into non-local impls
+#![allow(non_local_definitions)]
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Frequency, InformationRate, Length, ThermodynamicTemperature},
+    frequency::{gigahertz, hertz, megahertz},
+    information_rate::bit_per_second,
+    length::kilometer,
+    thermodynamic_temperature::kelvin,
+};
+
+use crate::{helper::SPEED_OF_LIGHT, representations::link_performance::LinkPerformance};
+
+/// Boltzmann constant in dBW/K/Hz.
+const BOLTZMANN_DB: f64 = -228.6;
+
+/// Lowest elevation used for the atmospheric loss, avoids the singularity of the cosecant law at the horizon.
+const MIN_ATMOSPHERIC_ELEVATION: f64 = 1.0;
+
+/// A modulation and coding scheme usable above a required SNR.
+#[derive(Debug, Clone, Copy)]
+pub struct Modcod {
+    /// Required signal-to-noise ratio in dB
+    min_snr: f64,
+    /// Spectral efficiency in bit/s/Hz
+    efficiency: f64,
+}
+
+/// Maps the signal-to-noise ratio of a link to its capacity.
+#[derive(Debug, Clone)]
+pub enum CapacityModel {
+    /// Shannon-Hartley limit
+    Shannon,
+    /// Most efficient scheme of the table whose required SNR is satisfied
+    Modcod(Vec<Modcod>),
+}
+
+impl CapacityModel {
+    fn capacity(&self, snr: f64, bandwidth: Frequency) -> InformationRate {
+        let bandwidth = bandwidth.get::<hertz>();
+        let capacity = match self {
+            CapacityModel::Shannon => bandwidth * f64::log2(1.0 + 10f64.powf(snr / 10.0)),
+            CapacityModel::Modcod(table) => table
+                .iter()
+                .filter(|modcod| modcod.min_snr <= snr)
+                .map(|modcod| modcod.efficiency * bandwidth)
+                .fold(0.0, f64::max),
+        };
+        InformationRate::new::<bit_per_second>(capacity)
+    }
+}
+
+/// Configurable RF or optical link budget.
+/// Gains, powers and losses are given in dB.
+#[pyclass(module = "link_budget")]
+#[derive(Debug, Clone)]
+pub struct LinkBudget {
+    /// Transmit power in dBW
+    transmit_power: f64,
+    /// Transmit antenna gain in dBi
+    transmit_gain: f64,
+    /// Receive antenna gain in dBi
+    receive_gain: f64,
+    frequency: Frequency,
+    bandwidth: Frequency,
+    /// System noise temperature of the receiver
+    noise_temperature: ThermodynamicTemperature,
+    /// Atmospheric loss at zenith in dB, scaled with the elevation for GSLs
+    zenith_atmospheric_loss: f64,
+    /// Further implementation and pointing losses in dB
+    other_losses: f64,
+    capacity_model: CapacityModel,
+}
+
+#[pymethods]
+impl LinkBudget {
+    /// Creates a link budget from python.
+    /// Frequency in GHz, bandwidth in MHz and noise temperature in K.
+    /// Without a MODCOD table given as (min SNR in dB, bit/s/Hz) pairs the Shannon capacity is used.
+    #[new]
+    #[pyo3(signature = (
+        transmit_power,
+        transmit_gain,
+        receive_gain,
+        frequency,
+        bandwidth,
+        noise_temperature,
+        zenith_atmospheric_loss = 0.0,
+        other_losses = 0.0,
+        modcods = None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        transmit_power: f64,
+        transmit_gain: f64,
+        receive_gain: f64,
+        frequency: f64,
+        bandwidth: f64,
+        noise_temperature: f64,
+        zenith_atmospheric_loss: f64,
+        other_losses: f64,
+        modcods: Option<Vec<(f64, f64)>>,
+    ) -> PyResult<Self> {
+        if !(frequency > 0.0 && bandwidth > 0.0 && noise_temperature > 0.0) {
+            return Err(PyValueError::new_err(
+                "frequency, bandwidth and noise temperature must be positive",
+            ));
+        }
+        let capacity_model = match modcods {
+            Some(table) => CapacityModel::Modcod(
+                table
+                    .into_iter()
+                    .map(|(min_snr, efficiency)| Modcod {
+                        min_snr,
+                        efficiency,
+                    })
+                    .collect(),
+            ),
+            None => CapacityModel::Shannon,
+        };
+        Ok(LinkBudget::new(
+            transmit_power,
+            transmit_gain,
+            receive_gain,
+            Frequency::new::<gigahertz>(frequency),
+            Frequency::new::<megahertz>(bandwidth),
+            ThermodynamicTemperature::new::<kelvin>(noise_temperature),
+            zenith_atmospheric_loss,
+            other_losses,
+            capacity_model,
+        ))
+    }
+}
+
+impl LinkBudget {
+    /// Creates a new link budget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the frequency, bandwidth or noise temperature is not positive.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        transmit_power: f64,
+        transmit_gain: f64,
+        receive_gain: f64,
+        frequency: Frequency,
+        bandwidth: Frequency,
+        noise_temperature: ThermodynamicTemperature,
+        zenith_atmospheric_loss: f64,
+        other_losses: f64,
+        capacity_model: CapacityModel,
+    ) -> Self {
+        assert!(frequency.get::<hertz>() > 0.0);
+        assert!(bandwidth.get::<hertz>() > 0.0);
+        assert!(noise_temperature.get::<kelvin>() > 0.0);
+        LinkBudget {
+            transmit_power,
+            transmit_gain,
+            receive_gain,
+            frequency,
+            bandwidth,
+            noise_temperature,
+            zenith_atmospheric_loss,
+            other_losses,
+            capacity_model,
+        }
+    }
+
+    pub fn get_frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Computes the free-space path loss in dB over the given distance.
+    pub fn free_space_path_loss(&self, distance: Length) -> f64 {
+        let distance = distance.get::<kilometer>();
+        let frequency = self.frequency.get::<hertz>();
+        20.0 * f64::log10(4.0 * std::f64::consts::PI * distance * frequency / SPEED_OF_LIGHT)
+    }
+
+    /// Computes the atmospheric loss in dB for the given elevation using the cosecant law.
+    /// Links without elevation (ISLs) do not pass through the atmosphere.
+    pub fn atmospheric_loss(&self, elevation: Option<Angle>) -> f64 {
+        match elevation {
+            Some(elevation) => {
+                let elevation = elevation
+                    .get::<degree>()
+                    .max(MIN_ATMOSPHERIC_ELEVATION)
+                    .to_radians();
+                self.zenith_atmospheric_loss / elevation.sin()
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Computes the signal-to-noise ratio in dB for the given distance and elevation (GSL only)
+    /// with additional losses in dB, e.g., rain attenuation.
+    pub fn snr(&self, distance: Length, elevation: Option<Angle>, additional_loss: f64) -> f64 {
+        let eirp = self.transmit_power + self.transmit_gain;
+        let received = eirp + self.receive_gain
+            - self.free_space_path_loss(distance)
+            - self.atmospheric_loss(elevation)
+            - self.other_losses
+            - additional_loss;
+        let noise = BOLTZMANN_DB
+            + 10.0 * f64::log10(self.noise_temperature.get::<kelvin>())
+            + 10.0 * f64::log10(self.bandwidth.get::<hertz>());
+        received - noise
+    }
+
+    /// Evaluates the budget of a link with the given distance, elevation (GSL only) and additional losses in dB.
+    pub fn evaluate(
+        &self,
+        distance: Length,
+        elevation: Option<Angle>,
+        additional_loss: f64,
+    ) -> LinkPerformance {
+        let snr = self.snr(distance, elevation, additional_loss);
+        LinkPerformance::new(snr, self.capacity_model.capacity(snr, self.bandwidth))
+    }
+}
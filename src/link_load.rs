@@ -0,0 +1,389 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufWriter, Error, Write},
+    path::Path,
+};
+
+use pyo3::{
+    exceptions::PyIOError,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use uom::si::{
+    f64::{InformationRate, Time},
+    information_rate::{bit_per_second, megabit_per_second},
+    time::millisecond,
+};
+
+use crate::{
+    constellation::{node::NodeId, Constellation},
+    representations::undirected_link::LinkType,
+    simulator::{neighbors, next_hops},
+    traffic::Demand,
+};
+
+/// Routed traffic over one direction of a link at a time step.
+#[derive(Debug, Clone, Copy)]
+pub struct LinkLoad {
+    /// Offset of the time step from the start of the report
+    time: Time,
+    link_type: LinkType,
+    from: NodeId,
+    to: NodeId,
+    load: InformationRate,
+    /// Capacity of the link, only available with a link budget
+    capacity: Option<InformationRate>,
+}
+
+impl LinkLoad {
+    pub fn get_time(&self) -> Time {
+        self.time
+    }
+
+    pub fn get_link_type(&self) -> LinkType {
+        self.link_type
+    }
+
+    pub fn get_from(&self) -> NodeId {
+        self.from
+    }
+
+    pub fn get_to(&self) -> NodeId {
+        self.to
+    }
+
+    pub fn get_load(&self) -> InformationRate {
+        self.load
+    }
+
+    pub fn get_capacity(&self) -> Option<InformationRate> {
+        self.capacity
+    }
+
+    /// Returns the ratio of the load to the capacity, if the capacity is known.
+    pub fn get_utilisation(&self) -> Option<f64> {
+        self.capacity
+            .filter(|capacity| capacity.get::<bit_per_second>() > 0.0)
+            .map(|capacity| (self.load / capacity).value)
+    }
+}
+
+/// Distribution of the loads of all links of one type at a time step.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadDistribution {
+    time: Time,
+    link_type: LinkType,
+    /// Number of link directions
+    links: usize,
+    mean: InformationRate,
+    median: InformationRate,
+    p95: InformationRate,
+    max: InformationRate,
+    /// Mean utilisation of the links with known capacity
+    mean_utilisation: Option<f64>,
+    /// Maximum utilisation of the links with known capacity
+    max_utilisation: Option<f64>,
+}
+
+impl LoadDistribution {
+    /// Summarizes the given loads, which must not be empty.
+    fn new(time: Time, link_type: LinkType, loads: &[&LinkLoad]) -> Self {
+        let mut rates: Vec<f64> = loads
+            .iter()
+            .map(|load| load.load.get::<bit_per_second>())
+            .collect();
+        rates.sort_by(f64::total_cmp);
+        // nearest-rank percentile of the sorted loads
+        let percentile = |p: f64| {
+            let rank = ((p * rates.len() as f64).ceil() as usize).clamp(1, rates.len());
+            InformationRate::new::<bit_per_second>(rates[rank - 1])
+        };
+        let utilisations: Vec<f64> = loads
+            .iter()
+            .filter_map(|load| load.get_utilisation())
+            .collect();
+        let mean_utilisation = if utilisations.is_empty() {
+            None
+        } else {
+            Some(utilisations.iter().sum::<f64>() / utilisations.len() as f64)
+        };
+        LoadDistribution {
+            time,
+            link_type,
+            links: loads.len(),
+            mean: InformationRate::new::<bit_per_second>(
+                rates.iter().sum::<f64>() / rates.len() as f64,
+            ),
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: percentile(1.0),
+            mean_utilisation,
+            max_utilisation: utilisations.into_iter().reduce(f64::max),
+        }
+    }
+
+    fn to_object(self, py: Python<'_>) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("time", self.time.get::<millisecond>())?;
+        dict.set_item("link_type", <&str>::from(self.link_type))?;
+        dict.set_item("links", self.links)?;
+        dict.set_item("mean", self.mean.get::<megabit_per_second>())?;
+        dict.set_item("median", self.median.get::<megabit_per_second>())?;
+        dict.set_item("p95", self.p95.get::<megabit_per_second>())?;
+        dict.set_item("max", self.max.get::<megabit_per_second>())?;
+        dict.set_item("mean_utilisation", self.mean_utilisation)?;
+        dict.set_item("max_utilisation", self.max_utilisation)?;
+        Ok(dict.into())
+    }
+}
+
+/// Link loads of a traffic matrix routed over the constellation at each time step.
+#[pyclass(module = "link_load")]
+#[derive(Debug, Clone)]
+pub struct LoadReport {
+    times: Vec<Time>,
+    /// Loads of both directions of all established links at each time step
+    loads: Vec<Vec<LinkLoad>>,
+    /// Demand without route to its destination at each time step
+    unrouted: Vec<InformationRate>,
+}
+
+impl LoadReport {
+    /// Routes the demands along the shortest paths of the constellation, i.e., the native routing
+    /// of the simulator, at each time step starting at the current epoch and lasting for the given duration.
+    pub(crate) fn new(
+        constellation: &Constellation,
+        demands: &[Demand],
+        duration: Time,
+        step: Time,
+    ) -> LoadReport {
+        assert!(step.get::<millisecond>() > 0.0);
+        assert!(duration.get::<millisecond>() >= 0.0);
+
+        let mut constellation = constellation.clone();
+        let groundstations: HashSet<NodeId> = constellation
+            .get_nodes()
+            .iter()
+            .filter(|node| node.get_node_type().is_ground())
+            .map(|node| node.get_id())
+            .collect();
+        let max_hops = constellation.node_count() as usize;
+        let mut report = LoadReport {
+            times: vec![],
+            loads: vec![],
+            unrouted: vec![],
+        };
+        let mut offset = Time::new::<millisecond>(0.0);
+        loop {
+            let neighbors = neighbors(&constellation);
+            let destinations: HashSet<NodeId> = demands
+                .iter()
+                .map(|demand| demand.get_destination())
+                .collect();
+            let routes: HashMap<NodeId, HashMap<NodeId, NodeId>> = destinations
+                .into_iter()
+                .map(|destination| {
+                    (
+                        destination,
+                        next_hops(&neighbors, &groundstations, destination),
+                    )
+                })
+                .collect();
+
+            let mut routed: HashMap<(NodeId, NodeId), f64> = HashMap::new();
+            let mut unrouted = 0.0;
+            for demand in demands {
+                let rate = demand.get_rate().get::<bit_per_second>();
+                let hops = &routes[&demand.get_destination()];
+                let mut path = vec![];
+                let mut node = demand.get_source();
+                while node != demand.get_destination() && path.len() < max_hops {
+                    let Some(next) = hops.get(&node) else {
+                        break;
+                    };
+                    path.push((node, *next));
+                    node = *next;
+                }
+                if node == demand.get_destination() {
+                    for key in path {
+                        *routed.entry(key).or_default() += rate;
+                    }
+                } else {
+                    unrouted += rate;
+                }
+            }
+
+            let mut loads = vec![];
+            for link in constellation.get_links() {
+                let (first, second) = link.get_key();
+                for (from, to) in [(first, second), (second, first)] {
+                    loads.push(LinkLoad {
+                        time: offset,
+                        link_type: link.link_type(),
+                        from,
+                        to,
+                        load: InformationRate::new::<bit_per_second>(
+                            routed.get(&(from, to)).copied().unwrap_or_default(),
+                        ),
+                        capacity: link.get_capacity(),
+                    });
+                }
+            }
+            report.times.push(offset);
+            report.loads.push(loads);
+            report
+                .unrouted
+                .push(InformationRate::new::<bit_per_second>(unrouted));
+
+            if offset >= duration {
+                break;
+            }
+            let next = if offset + step > duration {
+                duration
+            } else {
+                offset + step
+            };
+            constellation.propagate_time(next - offset);
+            offset = next;
+        }
+        report
+    }
+
+    /// Returns the most loaded link directions of each time step, ranked by their utilisation.
+    /// Links without known capacity follow, ranked by their load.
+    pub fn bottlenecks(&self, top: usize) -> Vec<LinkLoad> {
+        self.loads
+            .iter()
+            .flat_map(|loads| {
+                let mut loads = loads.clone();
+                loads.sort_by(|a, b| {
+                    let utilisation = |load: &LinkLoad| load.get_utilisation().unwrap_or(-1.0);
+                    utilisation(b)
+                        .total_cmp(&utilisation(a))
+                        .then(b.load.value.total_cmp(&a.load.value))
+                });
+                loads.truncate(top);
+                loads
+            })
+            .collect()
+    }
+
+    /// Returns the load distribution of each link type with established links at each time step.
+    pub fn distributions(&self) -> Vec<LoadDistribution> {
+        self.times
+            .iter()
+            .zip(self.loads.iter())
+            .flat_map(|(time, loads)| {
+                [LinkType::ISL, LinkType::GSL, LinkType::IOL]
+                    .into_iter()
+                    .filter_map(move |link_type| {
+                        let loads: Vec<&LinkLoad> = loads
+                            .iter()
+                            .filter(|load| load.link_type == link_type)
+                            .collect();
+                        (!loads.is_empty()).then(|| LoadDistribution::new(*time, link_type, &loads))
+                    })
+            })
+            .collect()
+    }
+
+    /// Writes the loads as CSV table with times in ms and rates in Mbit/s,
+    /// unknown capacities and utilisations are left empty.
+    pub(crate) fn write_table(&self, path: &Path) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "time,from,to,link_type,load,capacity,utilisation")?;
+        for load in self.loads.iter().flatten() {
+            let from: u32 = load.from.into();
+            let to: u32 = load.to.into();
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                load.time.get::<millisecond>(),
+                from,
+                to,
+                <&str>::from(load.link_type),
+                load.load.get::<megabit_per_second>(),
+                load.capacity
+                    .map(|capacity| capacity.get::<megabit_per_second>().to_string())
+                    .unwrap_or_default(),
+                load.get_utilisation()
+                    .map(|utilisation| utilisation.to_string())
+                    .unwrap_or_default(),
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+/// Converts the loads into a dict of columns, e.g., for a pandas DataFrame.
+fn columns<'a>(py: Python<'a>, loads: &[LinkLoad]) -> PyResult<&'a PyDict> {
+    let dict = PyDict::new(py);
+    let column =
+        |extract: &dyn Fn(&LinkLoad) -> PyObject| PyList::new(py, loads.iter().map(extract));
+    dict.set_item(
+        "time",
+        column(&|load| load.time.get::<millisecond>().to_object(py)),
+    )?;
+    dict.set_item("from", column(&|load| u32::from(load.from).to_object(py)))?;
+    dict.set_item("to", column(&|load| u32::from(load.to).to_object(py)))?;
+    dict.set_item(
+        "link_type",
+        column(&|load| <&str>::from(load.link_type).to_object(py)),
+    )?;
+    dict.set_item(
+        "load",
+        column(&|load| load.load.get::<megabit_per_second>().to_object(py)),
+    )?;
+    dict.set_item(
+        "capacity",
+        column(&|load| {
+            load.capacity
+                .map(|capacity| capacity.get::<megabit_per_second>())
+                .to_object(py)
+        }),
+    )?;
+    dict.set_item(
+        "utilisation",
+        column(&|load| load.get_utilisation().to_object(py)),
+    )?;
+    Ok(dict)
+}
+
+#[pymethods]
+impl LoadReport {
+    /// Returns the loads of both directions of all links at each time step as dict of columns
+    /// with times in ms and rates in Mbit/s.
+    fn table<'a>(&self, py: Python<'a>) -> PyResult<&'a PyDict> {
+        columns(py, &self.loads.concat())
+    }
+
+    /// Returns the `top` most utilised link directions of each time step as dict of columns.
+    #[pyo3(signature = (top = 10))]
+    fn top_bottlenecks<'a>(&self, py: Python<'a>, top: usize) -> PyResult<&'a PyDict> {
+        columns(py, &self.bottlenecks(top))
+    }
+
+    /// Returns the load distribution of ISLs and GSLs at each time step.
+    fn load_distributions(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.distributions()
+            .into_iter()
+            .map(|distribution| distribution.to_object(py))
+            .collect()
+    }
+
+    /// Returns the demand in Mbit/s without route to its destination at each time step.
+    fn unrouted(&self) -> Vec<(f64, f64)> {
+        self.times
+            .iter()
+            .zip(self.unrouted.iter())
+            .map(|(time, rate)| (time.get::<millisecond>(), rate.get::<megabit_per_second>()))
+            .collect()
+    }
+
+    /// Writes the loads as CSV table to the given file.
+    fn write(&self, path: &str) -> PyResult<()> {
+        self.write_table(Path::new(path))
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+}
@@ -48,12 +48,20 @@ pub struct Graph {
 
 #[pyclass(module = "internal_graph")]
 #[derive(Debug, Clone, Serialize)]
-pub struct InternalGraph {}
+pub struct InternalGraph {
+    /// ISLs that are failed, which are excluded from the links
+    pub failed_links: Vec<(u32, u32)>,
+}
 
 #[pyclass(module = "node")]
 #[derive(Debug, Clone, Copy, Serialize)]
 pub struct Node {
     pub id: u32,
+    /// Type of the node: satellite (S), ground station (G), mobile terminal (T) or relay (R)
+    #[serde(rename = "type")]
+    pub node_type: char,
+    /// Whether the node is failed, failed nodes have no links
+    pub failed: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -61,6 +69,55 @@ pub struct Link {
     pub weight: i32,
     pub source: u32,
     pub target: u32,
+    /// Type of the link: ISL, GSL or IOL
+    #[serde(rename = "type")]
+    pub link_type: &'static str,
+    /// Azimuth in degrees (GSL only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azimuth: Option<f64>,
+    /// Elevation in degrees (GSL only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elevation: Option<f64>,
+    /// Slant range in km (GSL only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<f64>,
+    /// Range rate in km/s (GSL only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_rate: Option<f64>,
+    /// Doppler shift in Hz (only with carrier frequency)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doppler_shift: Option<f64>,
+    /// Doppler rate in Hz/s (only with carrier frequency)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doppler_rate: Option<f64>,
+    /// Signal-to-noise ratio in dB (only with link budget)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snr: Option<f64>,
+    /// Capacity in Mbit/s (only with link budget)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<f64>,
+    /// Rain attenuation in dB (GSL only, with rain rate)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rain_attenuation: Option<f64>,
+}
+
+impl Link {
+    /// Returns the optional attributes which are available for this link.
+    pub fn attributes(&self) -> impl Iterator<Item = (&'static str, f64)> {
+        [
+            ("azimuth", self.azimuth),
+            ("elevation", self.elevation),
+            ("range", self.range),
+            ("range_rate", self.range_rate),
+            ("doppler_shift", self.doppler_shift),
+            ("doppler_rate", self.doppler_rate),
+            ("snr", self.snr),
+            ("capacity", self.capacity),
+            ("rain_attenuation", self.rain_attenuation),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+    }
 }
 
 impl Graph {
@@ -68,7 +125,9 @@ impl Graph {
         Graph {
             directed: false,
             multigraph: false,
-            graph: InternalGraph {},
+            graph: InternalGraph {
+                failed_links: vec![],
+            },
             nodes,
             links,
         }
@@ -83,8 +142,18 @@ impl ToPyObject for Graph {
 
         // add nodes
         self.nodes.iter().for_each(|node| {
-            graph.call_method("add_node", (node.id,), None).unwrap();
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("type", node.node_type).unwrap();
+            kwargs.set_item("failed", node.failed).unwrap();
+            graph
+                .call_method("add_node", (node.id,), Some(kwargs))
+                .unwrap();
         });
+        graph
+            .getattr("graph")
+            .unwrap()
+            .set_item("failed_links", self.graph.failed_links.clone())
+            .unwrap();
 
         // add edges
         self.links.iter().for_each(|link| {
@@ -92,6 +161,9 @@ impl ToPyObject for Graph {
             kwargs.set_item("u_of_edge", link.source).unwrap();
             kwargs.set_item("v_of_edge", link.target).unwrap();
             kwargs.set_item("weight", link.weight).unwrap();
+            kwargs.set_item("type", link.link_type).unwrap();
+            link.attributes()
+                .for_each(|(key, value)| kwargs.set_item(key, value).unwrap());
             graph.call_method("add_edge", (), Some(kwargs)).unwrap();
         });
 
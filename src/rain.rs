@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use nyx_space::time::Epoch;
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Frequency, Length, Time},
+    frequency::gigahertz,
+    length::kilometer,
+    time::millisecond,
+};
+
+use crate::{constellation::node::NodeId, helper};
+
+/// Effective earth radius in km used for low elevation slant paths.
+const EFFECTIVE_EARTH_RADIUS: f64 = 8500.0;
+
+/// Regression coefficients (a, b, c, m, c) of ITU-R P.838-3.
+struct Regression<const N: usize> {
+    a: [f64; N],
+    b: [f64; N],
+    c: [f64; N],
+    m: f64,
+    offset: f64,
+}
+
+impl<const N: usize> Regression<N> {
+    fn evaluate(&self, frequency: f64) -> f64 {
+        let x = frequency.log10();
+        let sum: f64 = (0..N)
+            .map(|j| self.a[j] * f64::exp(-((x - self.b[j]) / self.c[j]).powi(2)))
+            .sum();
+        sum + self.m * x + self.offset
+    }
+}
+
+const K_HORIZONTAL: Regression<4> = Regression {
+    a: [-5.33980, -0.35351, -0.23789, -0.94158],
+    b: [-0.10008, 1.26970, 0.86036, 0.64552],
+    c: [1.13098, 0.45400, 0.15354, 0.16817],
+    m: -0.18961,
+    offset: 0.71147,
+};
+
+const K_VERTICAL: Regression<4> = Regression {
+    a: [-3.80595, -3.44965, -0.39902, 0.50167],
+    b: [0.56934, -0.22911, 0.73042, 1.07319],
+    c: [0.81061, 0.51059, 0.11899, 0.27195],
+    m: -0.16398,
+    offset: 0.63297,
+};
+
+const ALPHA_HORIZONTAL: Regression<5> = Regression {
+    a: [-0.14318, 0.29591, 0.32177, -5.37610, 16.1721],
+    b: [1.82442, 0.77564, 0.63773, -0.96230, -3.29980],
+    c: [-0.55187, 0.19822, 0.13164, 1.47828, 3.43990],
+    m: 0.67849,
+    offset: -1.95537,
+};
+
+const ALPHA_VERTICAL: Regression<5> = Regression {
+    a: [-0.07771, 0.56727, -0.20238, -48.2991, 48.5833],
+    b: [2.33840, 0.95545, 1.14520, 0.791669, 0.791459],
+    c: [-0.76284, 0.54039, 0.26809, 0.116226, 0.116479],
+    m: -0.053739,
+    offset: 0.83433,
+};
+
+/// Rain rates in mm/h exceeded for 0.01% of an average year for the rain climatic zones of ITU-R P.837-1.
+const RAIN_ZONES: [(char, f64); 15] = [
+    ('A', 8.0),
+    ('B', 12.0),
+    ('C', 15.0),
+    ('D', 19.0),
+    ('E', 22.0),
+    ('F', 28.0),
+    ('G', 30.0),
+    ('H', 32.0),
+    ('J', 35.0),
+    ('K', 42.0),
+    ('L', 60.0),
+    ('M', 63.0),
+    ('N', 95.0),
+    ('P', 145.0),
+    ('Q', 115.0),
+];
+
+/// Returns the rain rate in mm/h exceeded for 0.01% of the year in the given rain climatic zone.
+pub(crate) fn zone_rain_rate(zone: char) -> Option<f64> {
+    RAIN_ZONES
+        .iter()
+        .find(|(name, _)| *name == zone.to_ascii_uppercase())
+        .map(|(_, rate)| *rate)
+}
+
+/// Computes the specific attenuation coefficients (k, α) for circular polarization (ITU-R P.838-3).
+fn specific_attenuation_coefficients(frequency: Frequency) -> (f64, f64) {
+    let frequency = frequency.get::<gigahertz>();
+    let k_h = 10f64.powf(K_HORIZONTAL.evaluate(frequency));
+    let k_v = 10f64.powf(K_VERTICAL.evaluate(frequency));
+    let alpha_h = ALPHA_HORIZONTAL.evaluate(frequency);
+    let alpha_v = ALPHA_VERTICAL.evaluate(frequency);
+    let k = (k_h + k_v) / 2.0;
+    let alpha = (k_h * alpha_h + k_v * alpha_v) / (2.0 * k);
+    (k, alpha)
+}
+
+/// Computes the rain height in km above mean sea level depending on the latitude (ITU-R P.839-2).
+fn rain_height(latitude: Angle) -> f64 {
+    let latitude = latitude.get::<degree>();
+    if latitude > 23.0 {
+        5.0 - 0.075 * (latitude - 23.0)
+    } else if latitude >= -21.0 {
+        5.0
+    } else if latitude >= -71.0 {
+        5.0 + 0.1 * (latitude + 21.0)
+    } else {
+        0.0
+    }
+}
+
+/// Computes the rain attenuation in dB along a slant path (ITU-R P.618, step 1 to 10)
+/// for a ground station at the given latitude and altitude with the given rain rate in mm/h.
+pub(crate) fn rain_attenuation(
+    latitude: Angle,
+    altitude: Length,
+    elevation: Angle,
+    frequency: Frequency,
+    rain_rate: f64,
+) -> f64 {
+    let rain_height = rain_height(latitude);
+    let station_height = altitude.get::<kilometer>();
+    let height = rain_height - station_height;
+    if rain_rate <= 0.0 || height <= 0.0 {
+        return 0.0;
+    }
+    let theta = elevation.get::<degree>().max(0.0);
+    let (sin_theta, cos_theta) = theta.to_radians().sin_cos();
+    let f = frequency.get::<gigahertz>();
+
+    // slant path length and its horizontal projection
+    let slant_length = if theta >= 5.0 {
+        height / sin_theta
+    } else {
+        2.0 * height
+            / ((sin_theta.powi(2) + 2.0 * height / EFFECTIVE_EARTH_RADIUS).sqrt() + sin_theta)
+    };
+    let horizontal_length = slant_length * cos_theta;
+
+    // specific attenuation
+    let (k, alpha) = specific_attenuation_coefficients(frequency);
+    let gamma = k * rain_rate.powf(alpha);
+
+    // horizontal reduction factor
+    let horizontal_reduction = 1.0
+        / (1.0 + 0.78 * (horizontal_length * gamma / f).sqrt()
+            - 0.38 * (1.0 - f64::exp(-2.0 * horizontal_length)));
+
+    // vertical adjustment factor
+    let zeta = f64::atan(height / (horizontal_length * horizontal_reduction)).to_degrees();
+    let rain_length = if zeta > theta {
+        horizontal_length * horizontal_reduction / cos_theta
+    } else {
+        height / sin_theta
+    };
+    let latitude = latitude.get::<degree>().abs();
+    let chi = if latitude < 36.0 {
+        36.0 - latitude
+    } else {
+        0.0
+    };
+    let vertical_adjustment = 1.0
+        / (1.0
+            + sin_theta.sqrt()
+                * (31.0 * (1.0 - f64::exp(-theta / (1.0 + chi))) * (rain_length * gamma).sqrt()
+                    / f.powi(2)
+                    - 0.45));
+
+    // attenuation along the effective path length
+    gamma * rain_length * vertical_adjustment
+}
+
+/// Rain rate experienced by a single ground station.
+#[derive(Debug, Clone)]
+pub enum RainRate {
+    /// Constant rain rate in mm/h
+    Constant(f64),
+    /// Rain rates in mm/h, linearly interpolated between the samples and held constant outside of them
+    TimeSeries(Vec<(Epoch, f64)>),
+}
+
+impl RainRate {
+    /// Returns the rain rate in mm/h at the given epoch.
+    fn at(&self, epoch: Epoch) -> f64 {
+        match self {
+            RainRate::Constant(rate) => *rate,
+            RainRate::TimeSeries(samples) => {
+                let index = samples.partition_point(|(sample, _)| *sample <= epoch);
+                match (
+                    index.checked_sub(1).map(|i| samples[i]),
+                    samples.get(index).copied(),
+                ) {
+                    (Some((_, rate)), None) | (None, Some((_, rate))) => rate,
+                    (Some((start, from)), Some((end, to))) => {
+                        let fraction = helper::time(epoch - start) / helper::time(end - start);
+                        from + (to - from) * fraction.value
+                    }
+                    (None, None) => 0.0,
+                }
+            }
+        }
+    }
+}
+
+/// Handling of GSLs whose rain attenuation exceeds the fade margin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FadePolicy {
+    /// The link is removed
+    Remove,
+    /// The link is kept and its capacity includes the attenuation exceeding the fade margin
+    Degrade,
+}
+
+/// Rain attenuation model of the GSLs of a constellation.
+#[derive(Debug, Clone)]
+pub struct RainModel {
+    /// Fade margin of the GSLs in dB, attenuations are not compared if unset
+    fade_margin: Option<f64>,
+    policy: FadePolicy,
+    rain_rates: HashMap<NodeId, RainRate>,
+}
+
+impl Default for RainModel {
+    fn default() -> Self {
+        RainModel {
+            fade_margin: None,
+            policy: FadePolicy::Degrade,
+            rain_rates: HashMap::new(),
+        }
+    }
+}
+
+impl RainModel {
+    /// Sets the fade margin in dB and the handling of links exceeding it.
+    pub fn set_fade_margin(&mut self, fade_margin: Option<f64>, policy: FadePolicy) {
+        self.fade_margin = fade_margin;
+        self.policy = policy;
+    }
+
+    /// Sets the rain rate of the given ground station.
+    pub fn set_rain_rate(&mut self, groundstation: NodeId, rain_rate: RainRate) {
+        self.rain_rates.insert(groundstation, rain_rate);
+    }
+
+    /// Removes the rain rate of the given ground station.
+    pub fn remove_rain_rate(&mut self, groundstation: NodeId) {
+        self.rain_rates.remove(&groundstation);
+    }
+
+    /// Returns the rain rate in mm/h at the given ground station and epoch,
+    /// or `None` if no rain rate was configured for the ground station.
+    pub fn rain_rate(&self, groundstation: NodeId, epoch: Epoch) -> Option<f64> {
+        self.rain_rates
+            .get(&groundstation)
+            .map(|rate| rate.at(epoch))
+    }
+
+    /// Checks whether a link with the given attenuation in dB must be removed.
+    pub fn is_removed(&self, attenuation: f64) -> bool {
+        self.policy == FadePolicy::Remove
+            && self
+                .fade_margin
+                .is_some_and(|fade_margin| attenuation > fade_margin)
+    }
+
+    /// Returns the part of the given attenuation in dB which is not covered by the fade margin
+    /// and hence reduces the capacity of the link, the whole attenuation if no fade margin is set.
+    pub fn excess_attenuation(&self, attenuation: f64) -> f64 {
+        match self.fade_margin {
+            Some(fade_margin) => (attenuation - fade_margin).max(0.0),
+            None => attenuation,
+        }
+    }
+}
+
+/// Reads rain rate time series from a CSV file with the columns `groundstation,time,rain_rate`.
+/// Times are milliseconds relative to the given start epoch, rain rates are given in mm/h.
+/// Empty lines, lines starting with `#` and a header line are skipped.
+pub(crate) fn read_time_series(
+    path: &Path,
+    start: Epoch,
+) -> Result<HashMap<String, RainRate>, Error> {
+    let content = fs::read_to_string(path)?;
+    let mut series: HashMap<String, Vec<(Epoch, f64)>> = HashMap::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("groundstation") {
+            continue;
+        }
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid rain rate in line {}: {}", number + 1, line),
+            )
+        };
+        let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [name, time, rate] = columns[..] else {
+            return Err(invalid());
+        };
+        let time: f64 = time.parse().map_err(|_| invalid())?;
+        let rate: f64 = rate.parse().map_err(|_| invalid())?;
+        let epoch = start + helper::duration(Time::new::<millisecond>(time));
+        series
+            .entry(name.to_string())
+            .or_default()
+            .push((epoch, rate));
+    }
+    Ok(series
+        .into_iter()
+        .map(|(name, mut samples)| {
+            samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            (name, RainRate::TimeSeries(samples))
+        })
+        .collect())
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use nyx_space::{linalg::Vector3, time::Epoch, Orbit};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Time},
+    length::kilometer,
+};
+
+use crate::{
+    constellation::node::{Node, NodeId, NodeType},
+    helper::{self, earth_fixed_frame, earth_frame},
+    networkx_graph::Node as NxNode,
+    representations::{lla::LLA, reference_frame::ReferenceFrame},
+    satellite::{normalize_longitude, propagate_orbit, Satellite},
+};
+
+/// Equatorial radius of the earth in km.
+pub(crate) const EARTH_RADIUS: f64 = 6378.137;
+
+/// Satellite in a higher orbit, e.g., GEO or MEO, which relays the traffic of the LEO satellites and ground stations.
+#[derive(Debug, Clone)]
+pub struct Relay {
+    id: NodeId,
+    /// Orbit in the inertial frame
+    orbit: Orbit,
+    /// Number of terminals for links to LEO satellites
+    terminals: u32,
+}
+
+impl Relay {
+    pub fn new(id: NodeId, orbit: Orbit, terminals: u32) -> Relay {
+        Relay {
+            id,
+            orbit,
+            terminals,
+        }
+    }
+
+    /// Creates a geostationary relay above the given longitude at the given epoch.
+    pub fn geostationary(id: NodeId, lon: Angle, epoch: Epoch, terminals: u32) -> Relay {
+        let fixed = earth_fixed_frame();
+        // radius at which the orbital period equals the rotation of the earth
+        let radius = (earth_frame().gm() / fixed.angular_velocity().powi(2)).cbrt();
+        let (sin_lon, cos_lon) = lon.get::<degree>().to_radians().sin_cos();
+        let orbit = Orbit::cartesian(
+            radius * cos_lon,
+            radius * sin_lon,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            epoch,
+            fixed,
+        );
+        Relay::new(id, ReferenceFrame::ECI.transform(&orbit), terminals)
+    }
+
+    /// Propagates the relay orbit for a given duration using the two-body propagator.
+    pub fn propagate(&mut self, step: Time) {
+        self.orbit = propagate_orbit(self.orbit, helper::duration(step));
+    }
+
+    /// Returns the orbit expressed in the earth-fixed frame, used for the geodetic coordinates.
+    fn get_orbit_fixed(&self) -> Orbit {
+        ReferenceFrame::ECEF.transform(&self.orbit)
+    }
+}
+
+impl Node for Relay {
+    fn get_id(&self) -> NodeId {
+        self.id
+    }
+
+    fn get_node_type(&self) -> NodeType {
+        NodeType::Relay
+    }
+
+    fn get_orbit(&self) -> Orbit {
+        self.orbit
+    }
+
+    fn get_acceleration(&self) -> Vector3<f64> {
+        // two-body gravitational acceleration
+        let radius = self.orbit.radius();
+        -self.orbit.frame.gm() * radius / radius.norm().powi(3)
+    }
+
+    fn get_position_lla(&self) -> LLA {
+        let lat = self.get_lat().get::<degree>();
+        let lon = self.get_lon().get::<degree>();
+        let alt = self.get_height().get::<kilometer>();
+        LLA::new(lat, lon, alt)
+    }
+
+    fn get_lat(&self) -> Angle {
+        Angle::new::<degree>(self.get_orbit_fixed().geodetic_latitude())
+    }
+
+    fn get_lon(&self) -> Angle {
+        let lon = self.get_orbit_fixed().geodetic_longitude();
+        Angle::new::<degree>(normalize_longitude(lon))
+    }
+
+    fn get_height(&self) -> Length {
+        Length::new::<kilometer>(self.get_orbit_fixed().geodetic_height())
+    }
+}
+
+impl From<Relay> for NxNode {
+    fn from(value: Relay) -> Self {
+        NxNode {
+            id: value.get_id().into(),
+            node_type: value.get_node_type().into(),
+            failed: false,
+        }
+    }
+}
+
+/// Rules for the links between LEO satellites and relays.
+#[derive(Debug, Clone, Copy)]
+pub struct RelayLinkRules {
+    /// Maximal length of a link, unlimited if not set
+    max_range: Option<Length>,
+    /// Minimal altitude of the line of sight above the earth, which keeps the links out of the atmosphere
+    grazing_altitude: Length,
+    /// Number of terminals of each LEO satellite for links to relays
+    satellite_terminals: u32,
+}
+
+impl Default for RelayLinkRules {
+    fn default() -> Self {
+        RelayLinkRules {
+            max_range: None,
+            grazing_altitude: Length::new::<kilometer>(100.0),
+            satellite_terminals: 1,
+        }
+    }
+}
+
+impl RelayLinkRules {
+    pub fn new(
+        max_range: Option<Length>,
+        grazing_altitude: Length,
+        satellite_terminals: u32,
+    ) -> Self {
+        RelayLinkRules {
+            max_range,
+            grazing_altitude,
+            satellite_terminals,
+        }
+    }
+
+    /// Checks whether the line of sight between both positions in km passes above the grazing altitude
+    /// and the distance does not exceed the maximal range.
+    fn is_feasible(&self, first: &Vector3<f64>, second: &Vector3<f64>) -> bool {
+        let rho = second - first;
+        let distance = rho.norm();
+        if self
+            .max_range
+            .is_some_and(|max_range| distance > max_range.get::<kilometer>())
+        {
+            return false;
+        }
+        // closest point of the line of sight to the center of the earth
+        let t = (-first.dot(&rho) / rho.norm_squared()).clamp(0.0, 1.0);
+        let closest = first + rho * t;
+        closest.norm() > EARTH_RADIUS + self.grazing_altitude.get::<kilometer>()
+    }
+}
+
+/// Selects the links between the LEO satellites and the relays, each link given by the relay, the satellite and the distance.
+/// Feasible pairs are assigned greedily by ascending distance
+/// as long as both the relay and the satellite have a free terminal.
+pub(crate) fn relay_links<'a>(
+    relays: &[Relay],
+    satellites: impl Iterator<Item = &'a Satellite>,
+    rules: &RelayLinkRules,
+) -> Vec<(NodeId, NodeId, Length)> {
+    let satellites: Vec<(NodeId, Vector3<f64>)> = satellites
+        .map(|sat| (sat.get_id(), sat.get_orbit().radius()))
+        .collect();
+    let mut candidates: Vec<(f64, usize, NodeId)> = relays
+        .iter()
+        .enumerate()
+        .flat_map(|(i, relay)| {
+            let position = relay.get_orbit().radius();
+            satellites
+                .iter()
+                .filter(move |(_, sat)| rules.is_feasible(&position, sat))
+                .map(move |(id, sat)| ((sat - position).norm(), i, *id))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut relay_terminals: Vec<u32> = relays.iter().map(|relay| relay.terminals).collect();
+    let mut satellite_terminals = HashMap::new();
+    let mut links = vec![];
+    for (distance, i, sat) in candidates {
+        let free = satellite_terminals
+            .entry(sat)
+            .or_insert(rules.satellite_terminals);
+        if relay_terminals[i] > 0 && *free > 0 {
+            relay_terminals[i] -= 1;
+            *free -= 1;
+            links.push((relays[i].id, sat, Length::new::<kilometer>(distance)));
+        }
+    }
+    links
+}
@@ -0,0 +1,51 @@
+use nyx_space::linalg::Vector3;
+use uom::si::{
+    f64::{Frequency, FrequencyDrift},
+    frequency::hertz,
+    frequency_drift::hertz_per_second,
+};
+
+use crate::{constellation::node::Node, helper::SPEED_OF_LIGHT};
+
+/// Doppler shift and Doppler rate of a carrier between two nodes.
+#[derive(Debug, Clone, Copy)]
+pub struct Doppler {
+    /// Frequency offset observed by the receiver, negative if the nodes move apart
+    shift: Frequency,
+    /// Change of the frequency offset
+    rate: FrequencyDrift,
+}
+
+impl Doppler {
+    /// Computes the Doppler shift and rate of the given carrier from the relative motion of both nodes.
+    /// Both nodes must provide their state in the same inertial frame.
+    pub(crate) fn between(first: &dyn Node, second: &dyn Node, carrier: Frequency) -> Doppler {
+        let first_orbit = first.get_orbit();
+        let second_orbit = second.get_orbit();
+        let rho: Vector3<f64> = second_orbit.radius() - first_orbit.radius();
+        let rho_dot: Vector3<f64> = second_orbit.velocity() - first_orbit.velocity();
+        let rho_ddot: Vector3<f64> = second.get_acceleration() - first.get_acceleration();
+
+        // derivatives of the range |ρ|
+        let range = rho.norm();
+        let range_rate = rho.dot(&rho_dot) / range;
+        let range_acceleration =
+            (rho_dot.norm_squared() + rho.dot(&rho_ddot) - range_rate.powi(2)) / range;
+
+        let carrier = carrier.get::<hertz>();
+        Doppler {
+            shift: Frequency::new::<hertz>(-carrier * range_rate / SPEED_OF_LIGHT),
+            rate: FrequencyDrift::new::<hertz_per_second>(
+                -carrier * range_acceleration / SPEED_OF_LIGHT,
+            ),
+        }
+    }
+
+    pub fn get_shift(&self) -> Frequency {
+        self.shift
+    }
+
+    pub fn get_rate(&self) -> FrequencyDrift {
+        self.rate
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashSet;
+
+use crate::{constellation::node::NodeId, representations::undirected_link::UndirectedLink};
+
+/// Links that were established and lost by recalculations of the constellation links.
+#[derive(Debug, Clone, Default)]
+pub struct LinkDiff {
+    added: Vec<UndirectedLink>,
+    removed: Vec<UndirectedLink>,
+}
+
+impl LinkDiff {
+    pub(crate) fn new(added: Vec<UndirectedLink>, removed: Vec<UndirectedLink>) -> LinkDiff {
+        LinkDiff { added, removed }
+    }
+
+    pub fn get_added(&self) -> &[UndirectedLink] {
+        &self.added
+    }
+
+    pub fn get_removed(&self) -> &[UndirectedLink] {
+        &self.removed
+    }
+
+    /// Appends the changes of the given diff, which must follow the changes of this diff.
+    /// Links added by this diff and removed by the given one, or vice versa, cancel out.
+    pub(crate) fn merge(&mut self, mut other: LinkDiff) {
+        let keys = |links: &[UndirectedLink]| -> HashSet<(NodeId, NodeId)> {
+            links.iter().map(|link| link.get_key()).collect()
+        };
+        let (added, removed) = (keys(&other.added), keys(&other.removed));
+        let transient: HashSet<_> = keys(&self.added).intersection(&removed).copied().collect();
+        let restored: HashSet<_> = keys(&self.removed).intersection(&added).copied().collect();
+        self.added
+            .retain(|link| !transient.contains(&link.get_key()));
+        other
+            .removed
+            .retain(|link| !transient.contains(&link.get_key()));
+        self.removed
+            .retain(|link| !restored.contains(&link.get_key()));
+        other
+            .added
+            .retain(|link| !restored.contains(&link.get_key()));
+        self.added.append(&mut other.added);
+        self.removed.append(&mut other.removed);
+    }
+}
@@ -0,0 +1,23 @@
+use uom::si::f64::InformationRate;
+
+/// Result of a link budget evaluation for a single link.
+#[derive(Debug, Clone, Copy)]
+pub struct LinkPerformance {
+    /// Signal-to-noise ratio in dB
+    snr: f64,
+    capacity: InformationRate,
+}
+
+impl LinkPerformance {
+    pub(crate) fn new(snr: f64, capacity: InformationRate) -> LinkPerformance {
+        LinkPerformance { snr, capacity }
+    }
+
+    pub fn get_snr(&self) -> f64 {
+        self.snr
+    }
+
+    pub fn get_capacity(&self) -> InformationRate {
+        self.capacity
+    }
+}
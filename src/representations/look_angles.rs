@@ -0,0 +1,45 @@
+use uom::si::f64::{Angle, Length, Velocity};
+
+/// Topocentric view of a satellite as seen from a ground station.
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    /// Azimuth measured clockwise from north
+    azimuth: Angle,
+    elevation: Angle,
+    /// Slant range between ground station and satellite
+    range: Length,
+    /// Change of the slant range, positive if the satellite moves away
+    range_rate: Velocity,
+}
+
+impl LookAngles {
+    pub(crate) fn new(
+        azimuth: Angle,
+        elevation: Angle,
+        range: Length,
+        range_rate: Velocity,
+    ) -> LookAngles {
+        LookAngles {
+            azimuth,
+            elevation,
+            range,
+            range_rate,
+        }
+    }
+
+    pub fn get_azimuth(&self) -> Angle {
+        self.azimuth
+    }
+
+    pub fn get_elevation(&self) -> Angle {
+        self.elevation
+    }
+
+    pub fn get_range(&self) -> Length {
+        self.range
+    }
+
+    pub fn get_range_rate(&self) -> Velocity {
+        self.range_rate
+    }
+}
@@ -1,2 +1,7 @@
 pub(crate) mod undirected_link;
-pub(crate) mod lla;
\ No newline at end of file
+pub(crate) mod lla;
+pub(crate) mod look_angles;
+pub(crate) mod doppler;
+pub(crate) mod link_performance;
+pub(crate) mod reference_frame;
+pub(crate) mod link_diff;
\ No newline at end of file
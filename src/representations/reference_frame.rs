@@ -0,0 +1,41 @@
+use nyx_space::{cosmic::Frame, Orbit};
+use pyo3::pyclass;
+
+use crate::helper::{cosm, earth_fixed_frame, earth_frame};
+
+/// Reference frame in which node positions are expressed.
+/// Distances between nodes are computed from their positions in the inertial frame,
+/// elevations and look angles of ground stations in the earth-fixed frame of the ground station.
+#[pyclass]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ReferenceFrame {
+    /// Earth-centered inertial frame (EME2000, i.e., J2000)
+    ECI,
+    /// Earth-centered, earth-fixed frame following the IAU rotation model of the earth.
+    /// It approximates ITRF, since it neither models nutation nor polar motion.
+    ECEF,
+}
+
+impl ReferenceFrame {
+    pub(crate) fn frame(&self) -> Frame {
+        match self {
+            ReferenceFrame::ECI => earth_frame(),
+            ReferenceFrame::ECEF => earth_fixed_frame(),
+        }
+    }
+
+    /// Returns the given orbit expressed in this frame.
+    pub(crate) fn transform(&self, orbit: &Orbit) -> Orbit {
+        cosm().frame_chg(orbit, self.frame())
+    }
+}
+
+impl From<ReferenceFrame> for &'static str {
+    fn from(frame: ReferenceFrame) -> Self {
+        match frame {
+            ReferenceFrame::ECI => "ECI",
+            ReferenceFrame::ECEF => "ECEF",
+        }
+    }
+}
@@ -1,12 +1,38 @@
-use uom::si::{f64::Length, length::kilometer};
+use uom::si::{
+    angle::degree,
+    f64::{InformationRate, Length},
+    frequency::hertz,
+    frequency_drift::hertz_per_second,
+    information_rate::megabit_per_second,
+    length::kilometer,
+    velocity::kilometer_per_second,
+};
 
-use crate::{constellation::node::NodeId, networkx_graph::Link as NxLink};
+use crate::{
+    constellation::node::NodeId,
+    networkx_graph::Link as NxLink,
+    representations::{
+        doppler::Doppler, link_performance::LinkPerformance, look_angles::LookAngles,
+    },
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum LinkType {
     ISL,
     GSL,
+    /// Inter-orbit link between a LEO satellite and a relay
+    IOL,
+}
+
+impl From<LinkType> for &'static str {
+    fn from(typ: LinkType) -> Self {
+        match typ {
+            LinkType::ISL => "ISL",
+            LinkType::GSL => "GSL",
+            LinkType::IOL => "IOL",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -15,6 +41,14 @@ pub struct UndirectedLink {
     first: NodeId,
     second: NodeId,
     distance: Length,
+    /// View of the satellite from the ground station, only available for GSLs
+    look_angles: Option<LookAngles>,
+    /// Doppler of the carrier, only available if a carrier frequency is configured
+    doppler: Option<Doppler>,
+    /// Result of the link budget, only available if a budget is configured for the link type
+    performance: Option<LinkPerformance>,
+    /// Rain attenuation in dB, only available for GSLs of ground stations with a rain rate
+    rain_attenuation: Option<f64>,
 }
 impl UndirectedLink {
     pub(crate) fn new_isl(first: NodeId, second: NodeId, distance: Length) -> UndirectedLink {
@@ -23,21 +57,101 @@ impl UndirectedLink {
             first,
             second,
             distance,
+            look_angles: None,
+            doppler: None,
+            performance: None,
+            rain_attenuation: None,
         }
     }
 
-    pub(crate) fn new_gsl(first: NodeId, second: NodeId, distance: Length) -> UndirectedLink {
+    /// Creates an inter-orbit link between a relay (first) and a LEO satellite (second).
+    pub(crate) fn new_iol(relay: NodeId, satellite: NodeId, distance: Length) -> UndirectedLink {
         UndirectedLink {
-            link_type: LinkType::GSL,
-            first,
-            second,
+            link_type: LinkType::IOL,
+            first: relay,
+            second: satellite,
             distance,
+            look_angles: None,
+            doppler: None,
+            performance: None,
+            rain_attenuation: None,
+        }
+    }
+
+    /// Creates a link between a ground station (first) and a satellite (second).
+    /// The distance of the link is the slant range of the look angles.
+    pub(crate) fn new_gsl(
+        groundstation: NodeId,
+        satellite: NodeId,
+        look_angles: LookAngles,
+    ) -> UndirectedLink {
+        UndirectedLink {
+            link_type: LinkType::GSL,
+            first: groundstation,
+            second: satellite,
+            distance: look_angles.get_range(),
+            look_angles: Some(look_angles),
+            doppler: None,
+            performance: None,
+            rain_attenuation: None,
         }
     }
 
     pub(crate) fn link_type(&self) -> LinkType {
         self.link_type
     }
+
+    pub(crate) fn get_first(&self) -> NodeId {
+        self.first
+    }
+
+    pub(crate) fn get_second(&self) -> NodeId {
+        self.second
+    }
+
+    /// Returns the IDs of both nodes in ascending order, which identifies the link regardless of its direction.
+    pub(crate) fn get_key(&self) -> (NodeId, NodeId) {
+        (self.first.min(self.second), self.first.max(self.second))
+    }
+
+    pub(crate) fn get_look_angles(&self) -> Option<LookAngles> {
+        self.look_angles
+    }
+
+    pub(crate) fn get_distance(&self) -> Length {
+        self.distance
+    }
+
+    /// Returns the capacity of the link budget, only available if a budget is configured for the link type.
+    pub(crate) fn get_capacity(&self) -> Option<InformationRate> {
+        self.performance
+            .map(|performance| performance.get_capacity())
+    }
+
+    pub(crate) fn get_rain_attenuation(&self) -> Option<f64> {
+        self.rain_attenuation
+    }
+
+    /// Returns this link with the given Doppler attached.
+    pub(crate) fn with_doppler(self, doppler: Option<Doppler>) -> UndirectedLink {
+        UndirectedLink { doppler, ..self }
+    }
+
+    /// Returns this link with the given rain attenuation attached.
+    pub(crate) fn with_rain_attenuation(self, rain_attenuation: Option<f64>) -> UndirectedLink {
+        UndirectedLink {
+            rain_attenuation,
+            ..self
+        }
+    }
+
+    /// Returns this link with the given link budget result attached.
+    pub(crate) fn with_performance(self, performance: Option<LinkPerformance>) -> UndirectedLink {
+        UndirectedLink {
+            performance,
+            ..self
+        }
+    }
 }
 
 impl From<UndirectedLink> for NxLink {
@@ -46,6 +160,30 @@ impl From<UndirectedLink> for NxLink {
             source: value.first.into(),
             target: value.second.into(),
             weight: value.distance.get::<kilometer>().round() as i32,
+            link_type: value.link_type.into(),
+            azimuth: value
+                .look_angles
+                .map(|look| look.get_azimuth().get::<degree>()),
+            elevation: value
+                .look_angles
+                .map(|look| look.get_elevation().get::<degree>()),
+            range: value
+                .look_angles
+                .map(|look| look.get_range().get::<kilometer>()),
+            range_rate: value
+                .look_angles
+                .map(|look| look.get_range_rate().get::<kilometer_per_second>()),
+            doppler_shift: value
+                .doppler
+                .map(|doppler| doppler.get_shift().get::<hertz>()),
+            doppler_rate: value
+                .doppler
+                .map(|doppler| doppler.get_rate().get::<hertz_per_second>()),
+            snr: value.performance.map(|performance| performance.get_snr()),
+            capacity: value
+                .performance
+                .map(|performance| performance.get_capacity().get::<megabit_per_second>()),
+            rain_attenuation: value.rain_attenuation,
         }
     }
 }
@@ -0,0 +1,526 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use nyx_space::linalg::DMatrix;
+use rayon::prelude::*;
+use uom::si::{
+    f64::Time,
+    length::kilometer,
+    time::{millisecond, second},
+};
+
+use crate::{
+    constellation::{node::NodeId, Constellation},
+    helper::SPEED_OF_LIGHT,
+};
+
+/// Maximum number of Lanczos iterations used for the algebraic connectivity.
+const MAX_LANCZOS_ITERATIONS: usize = 200;
+
+/// Graph-level metrics of the topology at a time step.
+#[derive(Debug, Clone)]
+pub struct RobustnessMetrics {
+    /// Offset of the time step from the start of the analysis
+    time: Time,
+    /// Number of connected components
+    components: usize,
+    /// Largest number of hops of a shortest path between connected nodes
+    hop_diameter: u32,
+    /// Largest propagation delay of a shortest path between connected nodes
+    delay_diameter: Time,
+    /// Mean number of hops between connected ground stations
+    mean_hops: Option<f64>,
+    /// Mean propagation delay between connected ground stations
+    mean_delay: Option<Time>,
+    /// Second smallest eigenvalue of the Laplacian, zero for disconnected topologies
+    algebraic_connectivity: f64,
+    /// Nodes whose removal disconnects their component
+    articulation_points: Vec<NodeId>,
+    /// Nodes with the highest normalized betweenness
+    node_betweenness: Vec<(NodeId, f64)>,
+    /// Links with the highest normalized betweenness
+    edge_betweenness: Vec<((NodeId, NodeId), f64)>,
+}
+
+impl RobustnessMetrics {
+    pub fn get_time(&self) -> Time {
+        self.time
+    }
+
+    pub fn get_components(&self) -> usize {
+        self.components
+    }
+
+    pub fn get_hop_diameter(&self) -> u32 {
+        self.hop_diameter
+    }
+
+    pub fn get_delay_diameter(&self) -> Time {
+        self.delay_diameter
+    }
+
+    pub fn get_mean_hops(&self) -> Option<f64> {
+        self.mean_hops
+    }
+
+    pub fn get_mean_delay(&self) -> Option<Time> {
+        self.mean_delay
+    }
+
+    pub fn get_algebraic_connectivity(&self) -> f64 {
+        self.algebraic_connectivity
+    }
+
+    pub fn get_articulation_points(&self) -> &[NodeId] {
+        &self.articulation_points
+    }
+
+    pub fn get_node_betweenness(&self) -> &[(NodeId, f64)] {
+        &self.node_betweenness
+    }
+
+    pub fn get_edge_betweenness(&self) -> &[((NodeId, NodeId), f64)] {
+        &self.edge_betweenness
+    }
+}
+
+/// Undirected graph of the working nodes and the established links.
+struct Topology {
+    nodes: Vec<NodeId>,
+    groundstations: Vec<bool>,
+    /// Links as pairs of node indices
+    edges: Vec<(usize, usize)>,
+    /// Neighbor, link index and propagation delay in s of each node
+    adjacency: Vec<Vec<(usize, usize, f64)>>,
+}
+
+impl Topology {
+    /// Builds the topology of the constellation, failed nodes are left out.
+    fn new(constellation: &Constellation) -> Self {
+        let nodes: Vec<(NodeId, bool)> = constellation
+            .get_nodes()
+            .iter()
+            .filter(|node| !constellation.is_failed(node.get_id()))
+            .map(|node| (node.get_id(), node.get_node_type().is_ground()))
+            .collect();
+        let indices: HashMap<NodeId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, i))
+            .collect();
+        let mut edges = vec![];
+        let mut adjacency = vec![vec![]; nodes.len()];
+        for link in constellation.get_links() {
+            let u = indices[&link.get_first()];
+            let v = indices[&link.get_second()];
+            let delay = link.get_distance().get::<kilometer>() / SPEED_OF_LIGHT;
+            adjacency[u].push((v, edges.len(), delay));
+            adjacency[v].push((u, edges.len(), delay));
+            edges.push((u, v));
+        }
+        Topology {
+            nodes: nodes.iter().map(|(id, _)| *id).collect(),
+            groundstations: nodes.iter().map(|(_, gs)| *gs).collect(),
+            edges,
+            adjacency,
+        }
+    }
+
+    fn components(&self) -> usize {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut components = 0;
+        for root in 0..self.nodes.len() {
+            if visited[root] {
+                continue;
+            }
+            components += 1;
+            visited[root] = true;
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for &(neighbor, _, _) in self.adjacency[node].iter() {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    /// Finds the articulation points with an iterative depth-first search (Tarjan).
+    fn articulation_points(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+        let mut discovery = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut articulation = vec![false; n];
+        let mut counter = 0;
+        for root in 0..n {
+            if discovery[root] != usize::MAX {
+                continue;
+            }
+            discovery[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            let mut root_children = 0;
+            // node, link used to reach it and index of its next neighbor
+            let mut stack = vec![(root, usize::MAX, 0)];
+            while let Some((node, via, next)) = stack.last_mut() {
+                if let Some(&(neighbor, edge, _)) = self.adjacency[*node].get(*next) {
+                    *next += 1;
+                    if edge == *via {
+                        continue;
+                    }
+                    if discovery[neighbor] == usize::MAX {
+                        discovery[neighbor] = counter;
+                        low[neighbor] = counter;
+                        counter += 1;
+                        stack.push((neighbor, edge, 0));
+                    } else {
+                        low[*node] = low[*node].min(discovery[neighbor]);
+                    }
+                } else {
+                    let child = *node;
+                    stack.pop();
+                    if let Some((parent, _, _)) = stack.last() {
+                        low[*parent] = low[*parent].min(low[child]);
+                        if *parent == root {
+                            root_children += 1;
+                        } else if low[child] >= discovery[*parent] {
+                            articulation[*parent] = true;
+                        }
+                    }
+                }
+            }
+            articulation[root] = root_children > 1;
+        }
+        (0..n).filter(|node| articulation[*node]).collect()
+    }
+
+    /// Approximates the second smallest eigenvalue of the Laplacian of a connected topology
+    /// with the Lanczos method on the subspace orthogonal to the constant vector.
+    fn algebraic_connectivity(&self) -> f64 {
+        let n = self.nodes.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let laplacian = |x: &[f64]| -> Vec<f64> {
+            (0..n)
+                .map(|i| self.adjacency[i].iter().map(|(j, _, _)| x[i] - x[*j]).sum())
+                .collect()
+        };
+        let dot = |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(a, b)| a * b).sum() };
+        let deflate = |x: &mut Vec<f64>| {
+            let mean = x.iter().sum::<f64>() / n as f64;
+            x.iter_mut().for_each(|value| *value -= mean);
+        };
+        let normalize = |x: &mut Vec<f64>| {
+            let norm = dot(x, x).sqrt();
+            x.iter_mut().for_each(|value| *value /= norm);
+        };
+
+        // deterministic start vector without constant component
+        let mut start: Vec<f64> = (0..n)
+            .map(|i| ((i * 7919 + 17) % 1009) as f64 / 1009.0 - 0.5)
+            .collect();
+        deflate(&mut start);
+        normalize(&mut start);
+        let mut basis: Vec<Vec<f64>> = vec![start];
+        let mut alphas = vec![];
+        let mut betas: Vec<f64> = vec![];
+        while alphas.len() < MAX_LANCZOS_ITERATIONS.min(n - 1) {
+            let current = basis.last().unwrap();
+            let mut w = laplacian(current);
+            alphas.push(dot(&w, current));
+            // full reorthogonalization keeps the basis orthogonal despite rounding errors
+            for vector in basis.iter() {
+                let projection = dot(&w, vector);
+                w.iter_mut()
+                    .zip(vector)
+                    .for_each(|(value, v)| *value -= projection * v);
+            }
+            deflate(&mut w);
+            let beta = dot(&w, &w).sqrt();
+            if beta < 1e-10 || alphas.len() == MAX_LANCZOS_ITERATIONS.min(n - 1) {
+                break;
+            }
+            betas.push(beta);
+            normalize(&mut w);
+            basis.push(w);
+        }
+
+        let k = alphas.len();
+        let tridiagonal = DMatrix::from_fn(k, k, |i, j| {
+            if i == j {
+                alphas[i]
+            } else if i + 1 == j {
+                betas[i]
+            } else if j + 1 == i {
+                betas[j]
+            } else {
+                0.0
+            }
+        });
+        tridiagonal
+            .symmetric_eigenvalues()
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+            .max(0.0)
+    }
+}
+
+/// Accumulated results of the shortest path searches from several sources.
+#[derive(Debug, Clone)]
+struct SourceResult {
+    /// Largest eccentricity in hops within the component of the source
+    hops: u32,
+    /// Largest eccentricity in s within the component of the source
+    delay: f64,
+    /// Number of pairs of connected ground stations
+    pairs: usize,
+    /// Sum of the hops to the reachable ground stations
+    pair_hops: f64,
+    /// Sum of the delays to the reachable ground stations
+    pair_delay: f64,
+    /// Summed dependencies of the nodes on the sources
+    node_betweenness: Vec<f64>,
+    /// Summed dependencies of the links on the sources
+    edge_betweenness: Vec<f64>,
+}
+
+impl SourceResult {
+    fn merge(mut self, other: SourceResult) -> SourceResult {
+        self.hops = self.hops.max(other.hops);
+        self.delay = self.delay.max(other.delay);
+        self.pairs += other.pairs;
+        self.pair_hops += other.pair_hops;
+        self.pair_delay += other.pair_delay;
+        self.node_betweenness
+            .iter_mut()
+            .zip(other.node_betweenness)
+            .for_each(|(value, other)| *value += other);
+        self.edge_betweenness
+            .iter_mut()
+            .zip(other.edge_betweenness)
+            .for_each(|(value, other)| *value += other);
+        self
+    }
+}
+
+/// Delay in a Dijkstra search, ordered by the shortest delay first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    delay: f64,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .delay
+            .total_cmp(&self.delay)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Runs a breadth-first search accumulating the betweenness (Brandes) and a Dijkstra search
+/// for the propagation delays from the source, and adds the results to the totals.
+/// Like the routing, paths do not transit ground stations other than the source.
+fn shortest_paths(topology: &Topology, source: usize, result: &mut SourceResult) {
+    let n = topology.nodes.len();
+
+    // hop distances and number of shortest paths
+    let mut distances = vec![u32::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut predecessors: Vec<Vec<(usize, usize)>> = vec![vec![]; n];
+    let mut order = vec![];
+    distances[source] = 0;
+    paths[source] = 1.0;
+    let mut queue = VecDeque::from([source]);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        if node != source && topology.groundstations[node] {
+            continue;
+        }
+        for &(neighbor, edge, _) in topology.adjacency[node].iter() {
+            if distances[neighbor] == u32::MAX {
+                distances[neighbor] = distances[node] + 1;
+                queue.push_back(neighbor);
+            }
+            if distances[neighbor] == distances[node] + 1 {
+                paths[neighbor] += paths[node];
+                predecessors[neighbor].push((node, edge));
+            }
+        }
+    }
+    result.hops = result.hops.max(distances[*order.last().unwrap()]);
+    // accumulate the dependencies in order of decreasing distance
+    let mut dependencies = vec![0.0; n];
+    for &node in order.iter().rev() {
+        for &(predecessor, edge) in predecessors[node].iter() {
+            let share = paths[predecessor] / paths[node] * (1.0 + dependencies[node]);
+            result.edge_betweenness[edge] += share;
+            dependencies[predecessor] += share;
+        }
+        if node != source {
+            result.node_betweenness[node] += dependencies[node];
+        }
+    }
+
+    // propagation delays
+    let mut delays = vec![f64::INFINITY; n];
+    delays[source] = 0.0;
+    let mut heap = BinaryHeap::from([Candidate {
+        delay: 0.0,
+        node: source,
+    }]);
+    while let Some(Candidate { delay, node }) = heap.pop() {
+        if delay > delays[node] || (node != source && topology.groundstations[node]) {
+            continue;
+        }
+        for &(neighbor, _, length) in topology.adjacency[node].iter() {
+            if delay + length < delays[neighbor] {
+                delays[neighbor] = delay + length;
+                heap.push(Candidate {
+                    delay: delay + length,
+                    node: neighbor,
+                });
+            }
+        }
+    }
+    result.delay = delays
+        .iter()
+        .copied()
+        .filter(|delay| delay.is_finite())
+        .fold(result.delay, f64::max);
+
+    if topology.groundstations[source] {
+        for node in (0..n).filter(|node| *node != source && topology.groundstations[*node]) {
+            if distances[node] != u32::MAX {
+                result.pairs += 1;
+                result.pair_hops += distances[node] as f64;
+                result.pair_delay += delays[node];
+            }
+        }
+    }
+}
+
+/// Computes the robustness metrics of the current topology of the constellation.
+/// Failed nodes are not part of the topology. The betweenness is normalized like networkx,
+/// only the `top` nodes and links with the highest betweenness are kept.
+fn snapshot(constellation: &Constellation, time: Time, top: usize) -> RobustnessMetrics {
+    let topology = Topology::new(constellation);
+    let n = topology.nodes.len();
+    let components = topology.components();
+    let empty = SourceResult {
+        hops: 0,
+        delay: 0.0,
+        pairs: 0,
+        pair_hops: 0.0,
+        pair_delay: 0.0,
+        node_betweenness: vec![0.0; n],
+        edge_betweenness: vec![0.0; topology.edges.len()],
+    };
+    let totals = (0..n)
+        .into_par_iter()
+        .fold(
+            || empty.clone(),
+            |mut totals, source| {
+                shortest_paths(&topology, source, &mut totals);
+                totals
+            },
+        )
+        .reduce(|| empty.clone(), SourceResult::merge);
+
+    let node_scale = if n > 2 {
+        1.0 / ((n - 1) * (n - 2)) as f64
+    } else {
+        0.0
+    };
+    let mut node_betweenness: Vec<(NodeId, f64)> = totals
+        .node_betweenness
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (topology.nodes[i], value * node_scale))
+        .collect();
+    node_betweenness.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    node_betweenness.truncate(top);
+    let edge_scale = if n > 1 {
+        1.0 / (n * (n - 1)) as f64
+    } else {
+        0.0
+    };
+    let mut edge_betweenness: Vec<((NodeId, NodeId), f64)> = totals
+        .edge_betweenness
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let (u, v) = topology.edges[i];
+            ((topology.nodes[u], topology.nodes[v]), value * edge_scale)
+        })
+        .collect();
+    edge_betweenness.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    edge_betweenness.truncate(top);
+
+    RobustnessMetrics {
+        time,
+        components,
+        hop_diameter: totals.hops,
+        delay_diameter: Time::new::<second>(totals.delay),
+        mean_hops: (totals.pairs > 0).then(|| totals.pair_hops / totals.pairs as f64),
+        mean_delay: (totals.pairs > 0)
+            .then(|| Time::new::<second>(totals.pair_delay / totals.pairs as f64)),
+        algebraic_connectivity: if components == 1 {
+            topology.algebraic_connectivity()
+        } else {
+            0.0
+        },
+        articulation_points: topology
+            .articulation_points()
+            .into_iter()
+            .map(|node| topology.nodes[node])
+            .collect(),
+        node_betweenness,
+        edge_betweenness,
+    }
+}
+
+/// Computes the robustness metrics at each time step starting at the current epoch and lasting for the given duration.
+pub(crate) fn robustness(
+    constellation: &Constellation,
+    duration: Time,
+    step: Time,
+    top: usize,
+) -> Vec<RobustnessMetrics> {
+    assert!(step.get::<millisecond>() > 0.0);
+    assert!(duration.get::<millisecond>() >= 0.0);
+
+    let mut constellation = constellation.clone();
+    let mut metrics = vec![];
+    let mut offset = Time::new::<millisecond>(0.0);
+    loop {
+        metrics.push(snapshot(&constellation, offset, top));
+        if offset >= duration {
+            break;
+        }
+        let next = if offset + step > duration {
+            duration
+        } else {
+            offset + step
+        };
+        constellation.propagate_time(next - offset);
+        offset = next;
+    }
+    metrics
+}
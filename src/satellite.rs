@@ -1,6 +1,7 @@
 use nyx_space::{
     cosmic::Frame,
     dynamics::OrbitalDynamics,
+    linalg::Vector3,
     propagators::Propagator,
     time::{Duration, Epoch},
     Orbit,
@@ -14,11 +15,12 @@ use uom::si::{
 };
 
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 
 use crate::{
-    constellation::node::{Node, NodeId, NodePosition, NodeType},
+    constellation::node::{Node, NodeId, NodeType},
     networkx_graph::Node as NxNode,
-    representations::lla::LLA,
+    representations::{lla::LLA, reference_frame::ReferenceFrame},
 };
 
 // Load the NASA NAIF DE438 planetary ephemeris.
@@ -27,11 +29,144 @@ static PROPAGATOR: Lazy<
     Propagator<'_, OrbitalDynamics<'_>, nyx_space::propagators::RSSCartesianStep>,
 > = Lazy::new(|| Propagator::default(OrbitalDynamics::two_body()));
 
+/// Propagates the given orbit for a duration using the two-body propagator.
+/// A zero duration returns the orbit unchanged.
+pub(crate) fn propagate_orbit(orbit: Orbit, duration: Duration) -> Orbit {
+    if duration == Duration::ZERO {
+        return orbit;
+    }
+    let mut prop = PROPAGATOR.with(orbit);
+    prop.for_duration(duration).unwrap()
+}
+
+/// Maps a longitude from [0°, 360°) to (-180°, 180°].
+pub(crate) fn normalize_longitude(mut lon: f64) -> f64 {
+    if lon > 180.0 {
+        lon -= 360.0;
+    }
+    lon
+}
+
+/// Maximal number of Newton iterations solving Kepler's equation.
+const MAX_KEPLER_ITERATIONS: usize = 50;
+
+/// Computes the true anomaly in radians from the mean anomaly in radians by solving Kepler's equation.
+fn true_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mean_anomaly = mean_anomaly.rem_euclid(std::f64::consts::TAU);
+    // Newton iteration on the eccentric anomaly, starting at π for high eccentricities
+    let mut eccentric_anomaly = if eccentricity > 0.8 {
+        std::f64::consts::PI
+    } else {
+        mean_anomaly
+    };
+    for _ in 0..MAX_KEPLER_ITERATIONS {
+        let delta = (eccentric_anomaly - eccentricity * eccentric_anomaly.sin() - mean_anomaly)
+            / (1.0 - eccentricity * eccentric_anomaly.cos());
+        eccentric_anomaly -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    let (sin_half, cos_half) = (eccentric_anomaly / 2.0).sin_cos();
+    2.0 * f64::atan2(
+        (1.0 + eccentricity).sqrt() * sin_half,
+        (1.0 - eccentricity).sqrt() * cos_half,
+    )
+}
+
+/// Shape of an orbit and its orientation within the orbital plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitShape {
+    /// Altitude of the perigee above the equatorial radius, which is the altitude of circular orbits
+    perigee_altitude: Length,
+    eccentricity: f64,
+    /// Argument of perigee, irrelevant for circular orbits
+    argument_of_perigee: Angle,
+}
+
+impl OrbitShape {
+    /// Creates the shape of a circular orbit at the given altitude.
+    pub fn circular(altitude: Length) -> Self {
+        OrbitShape {
+            perigee_altitude: altitude,
+            eccentricity: 0.0,
+            argument_of_perigee: Angle::new::<degree>(0.0),
+        }
+    }
+
+    /// Creates the shape of an elliptic orbit, e.g., Molniya or Tundra, with the perigee at the given altitude.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the eccentricity is not in [0, 1).
+    pub fn eccentric(
+        perigee_altitude: Length,
+        eccentricity: f64,
+        argument_of_perigee: Angle,
+    ) -> Self {
+        assert!((0.0..1.0).contains(&eccentricity));
+        OrbitShape {
+            perigee_altitude,
+            eccentricity,
+            argument_of_perigee,
+        }
+    }
+
+    pub fn get_perigee_altitude(&self) -> Length {
+        self.perigee_altitude
+    }
+
+    /// Returns this shape with the perigee at the given altitude.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the altitude is equal or below 0km.
+    pub fn with_perigee_altitude(self, perigee_altitude: Length) -> Self {
+        assert!(perigee_altitude.get::<kilometer>() > 0.0);
+        OrbitShape {
+            perigee_altitude,
+            ..self
+        }
+    }
+
+    /// Creates the orbit of this shape in the plane given by the inclination and RAAN.
+    /// The position on the orbit is given by the mean argument of latitude, i.e., the argument of perigee
+    /// plus the mean anomaly, which spaces satellites equally in time. For circular orbits it is the argument of latitude.
+    fn orbit(
+        &self,
+        mean_aol: Angle,
+        raan: Angle,
+        inclination: Angle,
+        dt: Epoch,
+        frame: Frame,
+    ) -> Orbit {
+        let sma = (self.perigee_altitude.get::<kilometer>() + frame.equatorial_radius())
+            / (1.0 - self.eccentricity);
+        let aop = self.argument_of_perigee.get::<degree>();
+        let mean_anomaly = mean_aol.get::<degree>() - aop;
+        let ta = if self.eccentricity == 0.0 {
+            mean_anomaly
+        } else {
+            true_anomaly(mean_anomaly.to_radians(), self.eccentricity).to_degrees()
+        };
+        Orbit::keplerian(
+            sma,
+            self.eccentricity,
+            inclination.get::<degree>(),
+            raan.get::<degree>(),
+            aop,
+            ta,
+            dt,
+            frame,
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct SatelliteNeighbors {
     id: NodeId,
-    top: NodeId,
-    right: NodeId,
+    top: Option<NodeId>,
+    right: Option<NodeId>,
     // bottom: NodeId,
     // left: NodeId,
 }
@@ -42,13 +177,13 @@ impl SatelliteNeighbors {
         self.id
     }
 
-    /// Returns the NodeId of the top neighbor (same plane, id in plane +1).
-    pub(crate) fn get_top(&self) -> NodeId {
+    /// Returns the NodeId of the top neighbor (same plane, next occupied slot).
+    pub(crate) fn get_top(&self) -> Option<NodeId> {
         self.top
     }
 
-    /// Returns the NodeId of the right neighbor (same id in plane, plane + 1).
-    pub(crate) fn get_right(&self) -> NodeId {
+    /// Returns the NodeId of the right neighbor (same slot, plane + 1).
+    pub(crate) fn get_right(&self) -> Option<NodeId> {
         self.right
     }
 
@@ -70,8 +205,8 @@ pub struct Satellite {
     id: NodeId,
     /// Plane index
     plane: u32,
-    /// Index of satellite in plane
-    number_in_plane: u32,
+    /// Index of the slot of the satellite in its plane, spares have no slot
+    number_in_plane: Option<u32>,
     /// Epoch of the satellite
     dt: Epoch,
     /// Orbit of the satellite
@@ -79,28 +214,21 @@ pub struct Satellite {
 }
 
 impl Satellite {
+    /// Creates a satellite on an orbit of the given shape at the mean argument of latitude `aol`,
+    /// which is the argument of latitude for circular orbits.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: NodeId,
         aol: Angle,
         raan: Angle,
         plane: u32,
-        number_in_plane: u32,
-        altitude: Length,
+        number_in_plane: Option<u32>,
+        shape: OrbitShape,
         inclination: Angle,
         dt: Epoch,
         frame: Frame,
     ) -> Satellite {
-        let orbit = Orbit::keplerian_altitude(
-            altitude.get::<kilometer>(),
-            0.0,
-            inclination.get::<degree>(),
-            raan.get::<degree>(),
-            0.0,
-            aol.get::<degree>(),
-            dt,
-            frame,
-        );
+        let orbit = shape.orbit(aol, raan, inclination, dt, frame);
         Satellite {
             id,
             plane,
@@ -110,6 +238,11 @@ impl Satellite {
         }
     }
 
+    /// Returns a satellite with the given ID which takes over the orbit and slot of this satellite.
+    pub(crate) fn replaced(&self, id: NodeId) -> Satellite {
+        Satellite { id, ..self.clone() }
+    }
+
     /// Propagates the satellite orbit for a given duration using the two-body propagator.
     pub fn propagate(&mut self, step: Time) {
         let duration = Duration::from_f64(
@@ -120,13 +253,13 @@ impl Satellite {
         //     "Propagate SAT({}-{}) for {}!",
         //     self.plane, self.number_in_plane, duration
         // );
-        let mut prop = PROPAGATOR.with(self.orbit);
-        self.orbit = prop.for_duration(duration).unwrap();
+        self.orbit = propagate_orbit(self.orbit, duration);
         self.dt += duration;
     }
 
-    pub fn get_orbit(&self) -> Orbit {
-        self.orbit
+    /// Returns the orbit expressed in the earth-fixed frame, used for the geodetic coordinates.
+    fn get_orbit_fixed(&self) -> Orbit {
+        ReferenceFrame::ECEF.transform(&self.orbit)
     }
 
     pub fn is_ascending(&self) -> bool {
@@ -138,20 +271,32 @@ impl Satellite {
         self.plane
     }
 
-    pub fn number_in_plane(&self) -> u32 {
+    pub fn number_in_plane(&self) -> Option<u32> {
         self.number_in_plane
     }
 
-    /// Computes all neighbor NodeIds of the given satellite in the constellation.
+    /// Computes all neighbor NodeIds of the given satellite in the constellation
+    /// from the IDs of the satellites occupying each (plane, slot).
+    /// The top neighbor skips empty slots, the right neighbor is missing if its slot is empty.
+    /// Spares without slot have no neighbors.
     pub(crate) fn get_neighbors(
         &self,
         sats_per_plane: u32,
         number_of_planes: u32,
+        slots: &HashMap<(u32, u32), NodeId>,
     ) -> SatelliteNeighbors {
-        let top_neighbor =
-            ((self.number_in_plane + 1) % sats_per_plane) + self.plane * sats_per_plane;
-        let right_neighbor =
-            ((self.plane + 1) % number_of_planes) * sats_per_plane + self.number_in_plane;
+        let top_neighbor = self.number_in_plane.and_then(|number_in_plane| {
+            (1..sats_per_plane)
+                .map(|offset| (number_in_plane + offset) % sats_per_plane)
+                .find_map(|slot| slots.get(&(self.plane, slot)).copied())
+        });
+        let right_neighbor = self.number_in_plane.and_then(|number_in_plane| {
+            let plane = (self.plane + 1) % number_of_planes;
+            slots
+                .get(&(plane, number_in_plane))
+                .copied()
+                .filter(|right| *right != self.id)
+        });
         // let bottom_neighbor = (self
         //     .number_in_plane
         //     .checked_sub(1)
@@ -162,8 +307,8 @@ impl Satellite {
         //     + self.number_in_plane;
         SatelliteNeighbors {
             id: self.id,
-            top: top_neighbor.into(),
-            right: right_neighbor.into(),
+            top: top_neighbor,
+            right: right_neighbor,
             // bottom: bottom_neighbor.into(),
             // left: left_neighbor.into(),
         }
@@ -179,23 +324,14 @@ impl Node for Satellite {
         NodeType::Satellite
     }
 
-    fn get_position_ecef(&self) -> NodePosition {
-        let x: Length = Length::new::<kilometer>(self.orbit.x);
-        let y: Length = Length::new::<kilometer>(self.orbit.y);
-        let z: Length = Length::new::<kilometer>(self.orbit.z);
-        NodePosition::new(x, y, z)
-    }
-
-    fn get_x(&self) -> Length {
-        Length::new::<kilometer>(self.orbit.x)
-    }
-
-    fn get_y(&self) -> Length {
-        Length::new::<kilometer>(self.orbit.y)
+    fn get_orbit(&self) -> Orbit {
+        self.orbit
     }
 
-    fn get_z(&self) -> Length {
-        Length::new::<kilometer>(self.orbit.z)
+    fn get_acceleration(&self) -> Vector3<f64> {
+        // two-body gravitational acceleration
+        let radius = self.orbit.radius();
+        -self.orbit.frame.gm() * radius / radius.norm().powi(3)
     }
 
     fn get_position_lla(&self) -> LLA {
@@ -206,19 +342,16 @@ impl Node for Satellite {
     }
 
     fn get_lat(&self) -> Angle {
-        Angle::new::<degree>(self.orbit.geodetic_latitude())
+        Angle::new::<degree>(self.get_orbit_fixed().geodetic_latitude())
     }
 
     fn get_lon(&self) -> Angle {
-        let mut lon = self.orbit.geodetic_longitude();
-        if lon > 180.0 {
-            lon -= 360.0;
-        }
-        Angle::new::<degree>(lon)
+        let lon = self.get_orbit_fixed().geodetic_longitude();
+        Angle::new::<degree>(normalize_longitude(lon))
     }
 
     fn get_height(&self) -> Length {
-        Length::new::<kilometer>(self.orbit.geodetic_height())
+        Length::new::<kilometer>(self.get_orbit_fixed().geodetic_height())
     }
 }
 
@@ -226,6 +359,8 @@ impl From<Satellite> for NxNode {
     fn from(value: Satellite) -> Self {
         NxNode {
             id: value.get_id().into(),
+            node_type: value.get_node_type().into(),
+            failed: false,
         }
     }
 }
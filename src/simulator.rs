@@ -0,0 +1,596 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use rand::{Rng, SeedableRng};
+use rand_distr::Exp;
+use rand_pcg::Pcg64;
+use uom::si::{
+    f64::{Information, InformationRate, Time},
+    information::bit,
+    information_rate::bit_per_second,
+    length::kilometer,
+    time::{millisecond, second},
+};
+
+use crate::{
+    constellation::{node::NodeId, Constellation},
+    helper::SPEED_OF_LIGHT,
+};
+
+/// Packets are dropped after this number of hops to break routing loops of static tables.
+const MAX_HOPS: u32 = 64;
+
+/// Forwarding decisions of the nodes.
+#[derive(Debug, Clone)]
+pub enum Routing {
+    /// Shortest paths by link distance, recomputed with the given interval.
+    /// Ground stations only forward packets that they send or receive.
+    ShortestPath { interval: Time },
+    /// Next hop of each (node, destination) pair
+    Static(HashMap<(NodeId, NodeId), NodeId>),
+}
+
+/// Distribution of the time between two packets of a flow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arrivals {
+    /// Packets are sent with a fixed interval
+    Constant,
+    /// Packets are sent with exponentially distributed intervals
+    Poisson,
+}
+
+/// Traffic from a source to a destination node.
+#[derive(Debug, Clone, Copy)]
+pub struct Flow {
+    source: NodeId,
+    destination: NodeId,
+    /// Average data rate of the flow
+    rate: InformationRate,
+    packet_size: Information,
+    arrivals: Arrivals,
+}
+
+impl Flow {
+    /// Creates a flow with a positive rate and packet size.
+    pub fn new(
+        source: NodeId,
+        destination: NodeId,
+        rate: InformationRate,
+        packet_size: Information,
+        arrivals: Arrivals,
+    ) -> Flow {
+        assert!(rate.get::<bit_per_second>() > 0.0);
+        assert!(packet_size.get::<bit>() > 0.0);
+        Flow {
+            source,
+            destination,
+            rate,
+            packet_size,
+            arrivals,
+        }
+    }
+
+    pub fn get_source(&self) -> NodeId {
+        self.source
+    }
+
+    pub fn get_destination(&self) -> NodeId {
+        self.destination
+    }
+}
+
+/// Parameters of a simulation run.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    duration: Time,
+    /// Interval of the topology updates, the links are constant in between
+    topology_step: Time,
+    routing: Routing,
+    /// Maximum number of packets waiting at each link direction
+    queue_size: usize,
+    /// Rate of links without link budget
+    default_rate: InformationRate,
+    seed: u64,
+}
+
+impl SimulationConfig {
+    pub fn new(
+        duration: Time,
+        topology_step: Time,
+        routing: Routing,
+        queue_size: usize,
+        default_rate: InformationRate,
+        seed: u64,
+    ) -> SimulationConfig {
+        SimulationConfig {
+            duration,
+            topology_step,
+            routing,
+            queue_size,
+            default_rate,
+            seed,
+        }
+    }
+}
+
+/// Results of a single flow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlowStatistics {
+    sent: u64,
+    received: u64,
+    dropped: u64,
+    latency_sum: f64,
+    min_latency: Option<f64>,
+    max_latency: f64,
+    received_bits: f64,
+    /// Simulated time in seconds
+    duration: f64,
+}
+
+impl FlowStatistics {
+    pub fn get_sent(&self) -> u64 {
+        self.sent
+    }
+
+    pub fn get_received(&self) -> u64 {
+        self.received
+    }
+
+    pub fn get_dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the share of the sent packets that were dropped.
+    /// Packets still in transit at the end of the simulation are not counted as lost.
+    pub fn get_loss(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            self.dropped as f64 / self.sent as f64
+        }
+    }
+
+    pub fn get_mean_latency(&self) -> Option<Time> {
+        (self.received > 0).then(|| Time::new::<second>(self.latency_sum / self.received as f64))
+    }
+
+    pub fn get_min_latency(&self) -> Option<Time> {
+        self.min_latency.map(Time::new::<second>)
+    }
+
+    pub fn get_max_latency(&self) -> Option<Time> {
+        (self.received > 0).then(|| Time::new::<second>(self.max_latency))
+    }
+
+    /// Returns the received data rate averaged over the simulated time.
+    pub fn get_throughput(&self) -> InformationRate {
+        let throughput = if self.duration > 0.0 {
+            self.received_bits / self.duration
+        } else {
+            0.0
+        };
+        InformationRate::new::<bit_per_second>(throughput)
+    }
+
+    fn record_delivery(&mut self, latency: f64, bits: f64) {
+        self.received += 1;
+        self.latency_sum += latency;
+        self.min_latency = Some(self.min_latency.map_or(latency, |min| min.min(latency)));
+        self.max_latency = self.max_latency.max(latency);
+        self.received_bits += bits;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Packet {
+    flow: usize,
+    /// Creation time in s
+    created: f64,
+    hops: u32,
+}
+
+/// Direction of a link with its packet queue.
+#[derive(Debug, Clone)]
+struct LinkState {
+    /// Rate in bit/s
+    rate: f64,
+    /// Propagation delay in s
+    delay: f64,
+    /// Identifies the establishment of the link, transmissions of a lost link are discarded
+    generation: u64,
+    busy: bool,
+    queue: VecDeque<Packet>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum EventKind {
+    /// The next packet of the flow is generated
+    Generate(usize),
+    /// The packet is completely received by the node
+    Arrival(Packet, NodeId),
+    /// The packet was completely sent over the link
+    Transmitted(Packet, (NodeId, NodeId), u64),
+    TopologyUpdate,
+    RoutingUpdate,
+}
+
+/// Event ordered by time and, for simultaneous events, by scheduling order.
+#[derive(Debug, Clone, Copy)]
+struct Event {
+    /// Time in s since the start of the simulation
+    time: f64,
+    sequence: u64,
+    kind: EventKind,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .total_cmp(&self.time)
+            .then(other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Packet-level discrete-event simulation of flows over the time-varying topology of a constellation.
+struct Simulation<'a> {
+    constellation: Constellation,
+    flows: &'a [Flow],
+    config: &'a SimulationConfig,
+    rng: Pcg64,
+    events: BinaryHeap<Event>,
+    sequence: u64,
+    generation: u64,
+    links: HashMap<(NodeId, NodeId), LinkState>,
+    /// Next hop of each (node, destination) pair
+    routes: HashMap<(NodeId, NodeId), NodeId>,
+    groundstations: HashSet<NodeId>,
+    statistics: Vec<FlowStatistics>,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(constellation: &Constellation, flows: &'a [Flow], config: &'a SimulationConfig) -> Self {
+        let groundstations = constellation
+            .get_nodes()
+            .iter()
+            .filter(|node| node.get_node_type().is_ground())
+            .map(|node| node.get_id())
+            .collect();
+        let routes = match &config.routing {
+            Routing::Static(routes) => routes.clone(),
+            Routing::ShortestPath { .. } => HashMap::new(),
+        };
+        Simulation {
+            constellation: constellation.clone(),
+            flows,
+            config,
+            rng: Pcg64::seed_from_u64(config.seed),
+            events: BinaryHeap::new(),
+            sequence: 0,
+            generation: 0,
+            links: HashMap::new(),
+            routes,
+            groundstations,
+            statistics: vec![FlowStatistics::default(); flows.len()],
+        }
+    }
+
+    fn schedule(&mut self, time: f64, kind: EventKind) {
+        self.events.push(Event {
+            time,
+            sequence: self.sequence,
+            kind,
+        });
+        self.sequence += 1;
+    }
+
+    fn run(mut self) -> Vec<FlowStatistics> {
+        let duration = self.config.duration.get::<second>();
+        self.update_links();
+        self.update_routes();
+        let topology_step = self.config.topology_step.get::<second>();
+        if topology_step > 0.0 && topology_step < duration {
+            self.schedule(topology_step, EventKind::TopologyUpdate);
+        }
+        if let Routing::ShortestPath { interval } = self.config.routing {
+            let interval = interval.get::<second>();
+            if interval > 0.0 && interval < duration {
+                self.schedule(interval, EventKind::RoutingUpdate);
+            }
+        }
+        for flow in 0..self.flows.len() {
+            let first = self.next_interval(flow);
+            if first < duration {
+                self.schedule(first, EventKind::Generate(flow));
+            }
+        }
+
+        while let Some(Event { time, kind, .. }) = self.events.pop() {
+            if time > duration {
+                break;
+            }
+            match kind {
+                EventKind::Generate(flow) => {
+                    self.statistics[flow].sent += 1;
+                    let packet = Packet {
+                        flow,
+                        created: time,
+                        hops: 0,
+                    };
+                    self.forward(packet, self.flows[flow].source, time);
+                    let next = time + self.next_interval(flow);
+                    if next < duration {
+                        self.schedule(next, EventKind::Generate(flow));
+                    }
+                }
+                EventKind::Arrival(packet, node) => self.forward(packet, node, time),
+                EventKind::Transmitted(packet, key, generation) => {
+                    self.transmitted(packet, key, generation, time)
+                }
+                EventKind::TopologyUpdate => {
+                    self.constellation.propagate_time(self.config.topology_step);
+                    self.update_links();
+                    // static tables and the previous shortest paths remain until the next routing update
+                    if time + topology_step < duration {
+                        self.schedule(time + topology_step, EventKind::TopologyUpdate);
+                    }
+                }
+                EventKind::RoutingUpdate => {
+                    self.update_routes();
+                    if let Routing::ShortestPath { interval } = self.config.routing {
+                        let next = time + interval.get::<second>();
+                        if next < duration {
+                            self.schedule(next, EventKind::RoutingUpdate);
+                        }
+                    }
+                }
+            }
+        }
+
+        for statistics in self.statistics.iter_mut() {
+            statistics.duration = duration;
+        }
+        self.statistics
+    }
+
+    /// Returns the time in s until the next packet of the given flow.
+    fn next_interval(&mut self, flow: usize) -> f64 {
+        let flow = &self.flows[flow];
+        let interval = (flow.packet_size / flow.rate).get::<second>();
+        match flow.arrivals {
+            Arrivals::Constant => interval,
+            Arrivals::Poisson => self.rng.sample(Exp::new(1.0 / interval).unwrap()),
+        }
+    }
+
+    /// Passes the packet received by the node to the next hop towards its destination.
+    fn forward(&mut self, mut packet: Packet, node: NodeId, time: f64) {
+        let flow = self.flows[packet.flow];
+        if node == flow.destination {
+            let bits = flow.packet_size.get::<bit>();
+            self.statistics[packet.flow].record_delivery(time - packet.created, bits);
+            return;
+        }
+        packet.hops += 1;
+        let next = self.routes.get(&(node, flow.destination)).copied();
+        let queue_size = self.config.queue_size;
+        let Some(link) = next
+            .filter(|_| packet.hops <= MAX_HOPS)
+            .and_then(|next| self.links.get_mut(&(node, next)))
+        else {
+            self.statistics[packet.flow].dropped += 1;
+            return;
+        };
+        if link.queue.len() >= queue_size {
+            self.statistics[packet.flow].dropped += 1;
+            return;
+        }
+        link.queue.push_back(packet);
+        if !link.busy {
+            self.start_transmission((node, next.unwrap()), time);
+        }
+    }
+
+    /// Starts the transmission of the first packet waiting at the link.
+    fn start_transmission(&mut self, key: (NodeId, NodeId), time: f64) {
+        let link = self.links.get_mut(&key).unwrap();
+        let Some(packet) = link.queue.pop_front() else {
+            link.busy = false;
+            return;
+        };
+        link.busy = true;
+        let bits = self.flows[packet.flow].packet_size.get::<bit>();
+        let end = time + bits / link.rate;
+        let generation = link.generation;
+        self.schedule(end, EventKind::Transmitted(packet, key, generation));
+    }
+
+    /// Propagates the sent packet to the receiving node and continues with the next waiting packet.
+    fn transmitted(&mut self, packet: Packet, key: (NodeId, NodeId), generation: u64, time: f64) {
+        match self.links.get(&key) {
+            Some(link) if link.generation == generation => {
+                let arrival = time + link.delay;
+                self.schedule(arrival, EventKind::Arrival(packet, key.1));
+                self.start_transmission(key, time);
+            }
+            // the link was lost during the transmission
+            _ => self.statistics[packet.flow].dropped += 1,
+        }
+    }
+
+    /// Takes the links of the current topology, keeping the queues of links that remain established.
+    /// Links without capacity cannot send packets and are left out.
+    /// Packets waiting at lost links are dropped.
+    fn update_links(&mut self) {
+        let mut links = HashMap::new();
+        let established: Vec<_> = self
+            .constellation
+            .get_links()
+            .map(|link| {
+                let rate = link
+                    .get_capacity()
+                    .unwrap_or(self.config.default_rate)
+                    .get::<bit_per_second>();
+                let delay = link.get_distance().get::<kilometer>() / SPEED_OF_LIGHT;
+                (link.get_first(), link.get_second(), rate, delay)
+            })
+            .filter(|(_, _, rate, _)| *rate > 0.0)
+            .collect();
+        for (u, v, rate, delay) in established {
+            for key in [(u, v), (v, u)] {
+                let state = match self.links.remove(&key) {
+                    Some(state) => LinkState {
+                        rate,
+                        delay,
+                        ..state
+                    },
+                    None => {
+                        self.generation += 1;
+                        LinkState {
+                            rate,
+                            delay,
+                            generation: self.generation,
+                            busy: false,
+                            queue: VecDeque::new(),
+                        }
+                    }
+                };
+                links.insert(key, state);
+            }
+        }
+        for state in self.links.values() {
+            for packet in state.queue.iter() {
+                self.statistics[packet.flow].dropped += 1;
+            }
+        }
+        self.links = links;
+    }
+
+    /// Recomputes the shortest paths towards all flow destinations.
+    fn update_routes(&mut self) {
+        if !matches!(self.config.routing, Routing::ShortestPath { .. }) {
+            return;
+        }
+        let neighbors = neighbors(&self.constellation);
+        let destinations: HashSet<NodeId> =
+            self.flows.iter().map(|flow| flow.destination).collect();
+        self.routes = destinations
+            .into_iter()
+            .flat_map(|destination| {
+                next_hops(&neighbors, &self.groundstations, destination)
+                    .into_iter()
+                    .map(move |(node, next)| ((node, destination), next))
+            })
+            .collect();
+    }
+}
+
+/// Distance in a Dijkstra search, ordered by the shortest distance first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f64,
+    node: NodeId,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Collects the neighbors of each node with the distance in km of the connecting link.
+/// Links whose link budget yields no capacity are left out, since they cannot carry traffic.
+pub(crate) fn neighbors(constellation: &Constellation) -> HashMap<NodeId, Vec<(NodeId, f64)>> {
+    let mut neighbors: HashMap<NodeId, Vec<(NodeId, f64)>> = HashMap::new();
+    for link in constellation.get_links().filter(|link| {
+        !link
+            .get_capacity()
+            .is_some_and(|capacity| capacity.value <= 0.0)
+    }) {
+        let distance = link.get_distance().get::<kilometer>();
+        neighbors
+            .entry(link.get_first())
+            .or_default()
+            .push((link.get_second(), distance));
+        neighbors
+            .entry(link.get_second())
+            .or_default()
+            .push((link.get_first(), distance));
+    }
+    neighbors
+}
+
+/// Computes the next hop of each node on its shortest path to the destination.
+/// Ground stations other than the destination do not relay traffic.
+pub(crate) fn next_hops(
+    neighbors: &HashMap<NodeId, Vec<(NodeId, f64)>>,
+    groundstations: &HashSet<NodeId>,
+    destination: NodeId,
+) -> HashMap<NodeId, NodeId> {
+    let mut distances: HashMap<NodeId, f64> = HashMap::from([(destination, 0.0)]);
+    let mut next_hops = HashMap::new();
+    let mut queue = BinaryHeap::from([Candidate {
+        distance: 0.0,
+        node: destination,
+    }]);
+    while let Some(Candidate { distance, node }) = queue.pop() {
+        if distances.get(&node).is_some_and(|best| distance > *best) {
+            continue;
+        }
+        if node != destination && groundstations.contains(&node) {
+            continue;
+        }
+        for (neighbor, length) in neighbors.get(&node).into_iter().flatten() {
+            let candidate = distance + length;
+            if distances.get(neighbor).is_none_or(|best| candidate < *best) {
+                distances.insert(*neighbor, candidate);
+                next_hops.insert(*neighbor, node);
+                queue.push(Candidate {
+                    distance: candidate,
+                    node: *neighbor,
+                });
+            }
+        }
+    }
+    next_hops
+}
+
+/// Simulates the given flows over the topology of the constellation starting at its current epoch.
+/// Packets are queued per link direction and sent with the rate of the link,
+/// the propagation delay follows from the link distance. The results only depend on the seed.
+pub(crate) fn simulate(
+    constellation: &Constellation,
+    flows: &[Flow],
+    config: &SimulationConfig,
+) -> Vec<FlowStatistics> {
+    assert!(config.duration.get::<millisecond>() >= 0.0);
+    assert!(config.topology_step.get::<millisecond>() > 0.0);
+    Simulation::new(constellation, flows, config).run()
+}
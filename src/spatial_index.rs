@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use nyx_space::linalg::Vector3;
+use uom::si::angle::radian;
+
+use crate::{constellation::node::Node, groundstation::Groundstation, satellite::Satellite};
+
+/// Margin of the minimal elevation used for pruning.
+/// Covers the deviation between the geodetic zenith of the ground station and its geocentric direction.
+const ELEVATION_MARGIN_DEG: f64 = 0.5;
+
+/// Relative margin of the maximum slant range to absorb rounding errors.
+const RANGE_MARGIN: f64 = 1.01;
+
+type Cell = (i64, i64, i64);
+
+/// Uniform grid over the inertial positions of the satellites.
+///
+/// Used to prune the satellites that are too far away from a ground station to be above its minimal elevation.
+/// The pruning is conservative, the exact elevation check still has to be applied to the candidates.
+pub(crate) struct SatelliteIndex {
+    /// Edge length of a cell in km
+    cell_size: f64,
+    /// Largest geocentric radius of all satellites in km
+    max_radius: f64,
+    cells: HashMap<Cell, Vec<usize>>,
+    positions: Vec<Vector3<f64>>,
+}
+
+impl SatelliteIndex {
+    /// Builds the index for the given satellites, with cells suitable for the given ground stations.
+    pub(crate) fn new(satellites: &[Satellite], groundstations: &[Groundstation]) -> Self {
+        let positions: Vec<Vector3<f64>> = satellites
+            .iter()
+            .map(|sat| sat.get_orbit().radius())
+            .collect();
+        let max_radius = positions
+            .iter()
+            .map(|position| position.norm())
+            .fold(0.0, f64::max);
+        let mut index = SatelliteIndex {
+            cell_size: 1.0,
+            max_radius,
+            cells: HashMap::new(),
+            positions,
+        };
+        // the cell size is the largest search radius, hence each query only checks the adjacent cells
+        index.cell_size = groundstations
+            .iter()
+            .map(|gs| index.max_slant_range(gs))
+            .fold(1.0, f64::max);
+        for (i, position) in index.positions.iter().enumerate() {
+            index.cells.entry(index.cell(position)).or_default().push(i);
+        }
+        index
+    }
+
+    fn cell(&self, position: &Vector3<f64>) -> Cell {
+        (
+            (position[0] / self.cell_size).floor() as i64,
+            (position[1] / self.cell_size).floor() as i64,
+            (position[2] / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// Computes an upper bound of the distance in km between the ground station
+    /// and any satellite of the index that is above the minimal elevation of the ground station.
+    fn max_slant_range(&self, groundstation: &Groundstation) -> f64 {
+        let rho = groundstation.get_orbit().rmag();
+        let r = self.max_radius;
+        let elevation = (groundstation.get_min_elevation().get::<radian>()
+            - ELEVATION_MARGIN_DEG.to_radians())
+        .max(-std::f64::consts::FRAC_PI_2);
+        if elevation < 0.0 || r <= rho {
+            // below the horizon the bound is the farthest point of the sphere of the satellites
+            return (r + rho) * RANGE_MARGIN;
+        }
+        // law of cosines in the triangle earth center, ground station, satellite
+        let (sin_e, cos_e) = elevation.sin_cos();
+        let range = -rho * sin_e + (r.powi(2) - (rho * cos_e).powi(2)).sqrt();
+        range * RANGE_MARGIN
+    }
+
+    /// Returns the indices (ascending) of all satellites that may be visible from the given ground station.
+    pub(crate) fn candidates(&self, groundstation: &Groundstation) -> Vec<usize> {
+        let position = groundstation.get_orbit().radius();
+        let max_range = self.max_slant_range(groundstation);
+        let (x, y, z) = self.cell(&position);
+        let mut candidates: Vec<usize> = (-1..=1)
+            .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+            .filter_map(|(dx, dy, dz)| self.cells.get(&(x + dx, y + dy, z + dz)))
+            .flatten()
+            .copied()
+            .filter(|i| (self.positions[*i] - position).norm() <= max_range)
+            .collect();
+        candidates.sort_unstable();
+        candidates
+    }
+}
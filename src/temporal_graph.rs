@@ -0,0 +1,170 @@
+use std::{fs::File, io::BufWriter, io::Error, path::Path};
+
+use pyo3::{types::PyDict, PyObject, Python, ToPyObject};
+use serde::Serialize;
+use uom::si::{f64::Time, length::kilometer, time::millisecond};
+
+use crate::{
+    constellation::Constellation, networkx_graph::Link as NxLink,
+    representations::undirected_link::UndirectedLink,
+};
+
+/// Time-expanded graph of a constellation over a time window.
+///
+/// The window is sampled with a fixed step, each sample is a layer with the links established at its epoch.
+/// Nodes are (node, layer) pairs, connected by the links of their layer
+/// and by storage edges from each node to itself in the next layer.
+#[derive(Debug, Clone)]
+pub struct TemporalGraph {
+    nodes: Vec<u32>,
+    /// Offsets of the layers relative to the start of the window
+    times: Vec<Time>,
+    layers: Vec<Vec<UndirectedLink>>,
+}
+
+impl TemporalGraph {
+    /// Samples the links of the given constellation starting at its current epoch
+    /// and lasting for the given duration. The last step is shortened to end at the duration.
+    pub(crate) fn new(constellation: &Constellation, duration: Time, step: Time) -> TemporalGraph {
+        assert!(step.get::<millisecond>() > 0.0);
+        assert!(duration.get::<millisecond>() >= 0.0);
+
+        let mut constellation = constellation.clone();
+        let nodes = constellation
+            .get_nodes()
+            .iter()
+            .map(|node| node.get_id().into())
+            .collect();
+        let mut times = vec![];
+        let mut layers = vec![];
+        let mut offset = Time::new::<millisecond>(0.0);
+        loop {
+            times.push(offset);
+            layers.push(constellation.get_links().copied().collect());
+            if offset >= duration {
+                break;
+            }
+            let next = if offset + step > duration {
+                duration
+            } else {
+                offset + step
+            };
+            constellation.propagate_time(next - offset);
+            offset = next;
+        }
+        TemporalGraph {
+            nodes,
+            times,
+            layers,
+        }
+    }
+
+    pub fn get_times(&self) -> &[Time] {
+        &self.times
+    }
+
+    pub fn get_layers(&self) -> &[Vec<UndirectedLink>] {
+        &self.layers
+    }
+
+    /// Writes the graph in a compact JSON format: the node IDs, the offsets of the layers in ms
+    /// and the links as `[layer, source, target, weight]`. Storage edges are implicit.
+    pub(crate) fn write(&self, path: &Path) -> Result<(), Error> {
+        let compact = CompactTemporalGraph {
+            nodes: &self.nodes,
+            times: self
+                .times
+                .iter()
+                .map(|time| time.get::<millisecond>())
+                .collect(),
+            links: self
+                .layers
+                .iter()
+                .enumerate()
+                .flat_map(|(layer, links)| {
+                    links.iter().map(move |link| {
+                        (
+                            layer,
+                            link.get_first().into(),
+                            link.get_second().into(),
+                            link.get_distance().get::<kilometer>().round() as i32,
+                        )
+                    })
+                })
+                .collect(),
+        };
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, &compact)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct CompactTemporalGraph<'a> {
+    nodes: &'a [u32],
+    times: Vec<f64>,
+    links: Vec<(usize, u32, u32, i32)>,
+}
+
+impl ToPyObject for TemporalGraph {
+    /// Creates a directed networkx graph with (node, layer) tuples as nodes.
+    /// Links are added in both directions with `kind="link"`,
+    /// storage edges with `kind="storage"`, zero weight and their duration in ms.
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        let module = py.import("networkx").unwrap();
+        let graph = module.getattr("DiGraph").unwrap();
+        let graph = graph.call0().unwrap();
+
+        // add nodes
+        self.times.iter().enumerate().for_each(|(layer, time)| {
+            self.nodes.iter().for_each(|node| {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("node", node).unwrap();
+                kwargs.set_item("time", time.get::<millisecond>()).unwrap();
+                graph
+                    .call_method("add_node", ((*node, layer),), Some(kwargs))
+                    .unwrap();
+            });
+        });
+
+        // add links
+        self.layers.iter().enumerate().for_each(|(layer, links)| {
+            links.iter().for_each(|link| {
+                let link: NxLink = (*link).into();
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("kind", "link").unwrap();
+                kwargs.set_item("weight", link.weight).unwrap();
+                link.attributes()
+                    .for_each(|(key, value)| kwargs.set_item(key, value).unwrap());
+                for (u, v) in [(link.source, link.target), (link.target, link.source)] {
+                    graph
+                        .call_method("add_edge", ((u, layer), (v, layer)), Some(kwargs))
+                        .unwrap();
+                }
+            });
+        });
+
+        // add storage edges
+        self.times
+            .windows(2)
+            .enumerate()
+            .for_each(|(layer, window)| {
+                let duration = (window[1] - window[0]).get::<millisecond>();
+                self.nodes.iter().for_each(|node| {
+                    let kwargs = PyDict::new(py);
+                    kwargs.set_item("kind", "storage").unwrap();
+                    kwargs.set_item("weight", 0).unwrap();
+                    kwargs.set_item("duration", duration).unwrap();
+                    graph
+                        .call_method(
+                            "add_edge",
+                            ((*node, layer), (*node, layer + 1)),
+                            Some(kwargs),
+                        )
+                        .unwrap();
+                });
+            });
+
+        graph.to_object(py)
+    }
+}
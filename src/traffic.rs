@@ -0,0 +1,136 @@
+use uom::si::{
+    angle::radian,
+    f64::{Angle, InformationRate, Length},
+    length::kilometer,
+};
+
+use crate::{
+    constellation::node::{Node, NodeId},
+    groundstation::Groundstation,
+};
+
+/// Mean earth radius in km used for great-circle distances.
+const MEAN_EARTH_RADIUS: f64 = 6371.0;
+
+/// Lower bound of the distance in km between two ground stations of the gravity model,
+/// avoids infinite demands between co-located stations.
+const MIN_GRAVITY_DISTANCE: f64 = 1.0;
+
+/// Distribution of the traffic between the ground stations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrafficModel {
+    /// Equal demand between all pairs of ground stations
+    Uniform,
+    /// Demand proportional to the product of the station weights,
+    /// divided by the great-circle distance raised to the given exponent
+    Gravity { distance_exponent: f64 },
+    /// Demand from each terminal to its nearest gateway proportional to the terminal weight
+    Gateway,
+}
+
+/// Traffic demand from a source to a destination node.
+#[derive(Debug, Clone, Copy)]
+pub struct Demand {
+    source: NodeId,
+    destination: NodeId,
+    rate: InformationRate,
+}
+
+impl Demand {
+    pub fn new(source: NodeId, destination: NodeId, rate: InformationRate) -> Self {
+        Demand {
+            source,
+            destination,
+            rate,
+        }
+    }
+
+    pub fn get_source(&self) -> NodeId {
+        self.source
+    }
+
+    pub fn get_destination(&self) -> NodeId {
+        self.destination
+    }
+
+    pub fn get_rate(&self) -> InformationRate {
+        self.rate
+    }
+}
+
+/// Computes the great-circle distance between two ground stations.
+fn great_circle_distance(first: &Groundstation, second: &Groundstation) -> Length {
+    let (lat1, lon1) = (first.get_lat(), first.get_lon());
+    let (lat2, lon2) = (second.get_lat(), second.get_lon());
+    let haversine = |angle: Angle| (angle.get::<radian>() / 2.0).sin().powi(2);
+    let a = haversine(lat2 - lat1)
+        + lat1.get::<radian>().cos() * lat2.get::<radian>().cos() * haversine(lon2 - lon1);
+    Length::new::<kilometer>(2.0 * MEAN_EARTH_RADIUS * a.sqrt().min(1.0).asin())
+}
+
+/// Generates the demands between the given ground stations following the model.
+/// The demands are scaled to sum up to the given total rate, pairs without demand are omitted.
+pub(crate) fn traffic_matrix(
+    groundstations: &[Groundstation],
+    model: TrafficModel,
+    total: InformationRate,
+) -> Vec<Demand> {
+    let pairs: Vec<(&Groundstation, &Groundstation, f64)> = match model {
+        TrafficModel::Uniform => groundstations
+            .iter()
+            .flat_map(|source| {
+                groundstations
+                    .iter()
+                    .filter(move |destination| destination.get_id() != source.get_id())
+                    .map(move |destination| (source, destination, 1.0))
+            })
+            .collect(),
+        TrafficModel::Gravity { distance_exponent } => groundstations
+            .iter()
+            .flat_map(|source| {
+                groundstations
+                    .iter()
+                    .filter(move |destination| destination.get_id() != source.get_id())
+                    .map(move |destination| {
+                        let distance = great_circle_distance(source, destination)
+                            .get::<kilometer>()
+                            .max(MIN_GRAVITY_DISTANCE);
+                        let weight = source.get_weight() * destination.get_weight()
+                            / distance.powf(distance_exponent);
+                        (source, destination, weight)
+                    })
+            })
+            .collect(),
+        TrafficModel::Gateway => groundstations
+            .iter()
+            .filter(|terminal| !terminal.is_gateway())
+            .filter_map(|terminal| {
+                groundstations
+                    .iter()
+                    .filter(|gateway| gateway.is_gateway())
+                    .min_by(|a, b| {
+                        great_circle_distance(terminal, a)
+                            .value
+                            .total_cmp(&great_circle_distance(terminal, b).value)
+                    })
+                    .map(|gateway| (terminal, gateway, terminal.get_weight()))
+            })
+            .collect(),
+    };
+
+    let sum: f64 = pairs.iter().map(|(_, _, weight)| weight).sum();
+    if sum <= 0.0 {
+        return vec![];
+    }
+    pairs
+        .into_iter()
+        .filter(|(_, _, weight)| *weight > 0.0)
+        .map(|(source, destination, weight)| {
+            Demand::new(
+                source.get_id(),
+                destination.get_id(),
+                total * (weight / sum),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+use nyx_space::{
+    linalg::Vector3,
+    time::{Duration, Epoch},
+};
+use uom::si::{
+    angle::radian,
+    f64::{Angle, Length, Time, Velocity},
+    time::second,
+    velocity::kilometer_per_second,
+};
+
+use crate::helper;
+
+/// Mean radius of the earth in km used for the great-circle distances.
+const MEAN_EARTH_RADIUS: f64 = 6371.0;
+
+/// Tolerance of the central angle in radians below π at which two positions are considered antipodal.
+const ANTIPODAL_TOLERANCE: f64 = 1e-9;
+
+/// Position of a mobile node at an epoch.
+#[derive(Debug, Clone, Copy)]
+pub struct Waypoint {
+    lat: Angle,
+    lon: Angle,
+    alt: Length,
+    epoch: Epoch,
+}
+
+impl Waypoint {
+    pub fn new(lat: Angle, lon: Angle, alt: Length, epoch: Epoch) -> Self {
+        Waypoint {
+            lat,
+            lon,
+            alt,
+            epoch,
+        }
+    }
+
+    /// Returns the unit vector pointing to the waypoint on a spherical earth.
+    fn direction(&self) -> Vector3<f64> {
+        let (sin_lat, cos_lat) = self.lat.get::<radian>().sin_cos();
+        let (sin_lon, cos_lon) = self.lon.get::<radian>().sin_cos();
+        Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat)
+    }
+}
+
+/// Trajectory of a mobile node through waypoints ordered by their epochs.
+/// Between two waypoints the node follows the great circle with constant speed and linearly changing altitude.
+/// Before the first and after the last waypoint it stays at the respective waypoint.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    waypoints: Vec<Waypoint>,
+}
+
+impl Trajectory {
+    /// Creates the trajectory through the given waypoints, which must have strictly increasing epochs.
+    /// Returns `None` without waypoints, for unordered epochs or for antipodal consecutive waypoints,
+    /// since the great circle between them is ambiguous.
+    pub fn new(waypoints: Vec<Waypoint>) -> Option<Self> {
+        let valid = !waypoints.is_empty()
+            && waypoints.windows(2).all(|pair| {
+                pair[0].epoch < pair[1].epoch
+                    && central_angle(&pair[0].direction(), &pair[1].direction())
+                        < std::f64::consts::PI - ANTIPODAL_TOLERANCE
+            });
+        valid.then_some(Trajectory { waypoints })
+    }
+
+    /// Creates the trajectory along the great circle between the given (latitude, longitude) positions
+    /// at a constant altitude, departing at the given epoch with the given positive ground speed.
+    /// Returns `None` for antipodal positions.
+    pub fn great_circle(
+        from: (Angle, Angle),
+        to: (Angle, Angle),
+        alt: Length,
+        speed: Velocity,
+        departure: Epoch,
+    ) -> Option<Self> {
+        assert!(speed.get::<kilometer_per_second>() > 0.0);
+        let start = Waypoint::new(from.0, from.1, alt, departure);
+        let end = Waypoint::new(to.0, to.1, alt, departure);
+        let distance = central_angle(&start.direction(), &end.direction()) * MEAN_EARTH_RADIUS;
+        let travel_time: Time = Time::new::<second>(distance / speed.get::<kilometer_per_second>());
+        if helper::duration(travel_time) <= Duration::ZERO {
+            return Trajectory::new(vec![start]);
+        }
+        let end = Waypoint {
+            epoch: departure + helper::duration(travel_time),
+            ..end
+        };
+        Trajectory::new(vec![start, end])
+    }
+
+    /// Returns latitude, longitude and altitude at the given epoch.
+    pub fn position(&self, epoch: Epoch) -> (Angle, Angle, Length) {
+        let next = self
+            .waypoints
+            .partition_point(|waypoint| waypoint.epoch <= epoch);
+        if next == 0 || next == self.waypoints.len() {
+            let waypoint = self.waypoints[next.saturating_sub(1)];
+            return (waypoint.lat, waypoint.lon, waypoint.alt);
+        }
+        let (previous, next) = (self.waypoints[next - 1], self.waypoints[next]);
+        let fraction =
+            (epoch - previous.epoch).in_seconds() / (next.epoch - previous.epoch).in_seconds();
+
+        // spherical linear interpolation between the directions of both waypoints
+        let (from, to) = (previous.direction(), next.direction());
+        let angle = central_angle(&from, &to);
+        let direction = if angle < 1e-12 {
+            from
+        } else {
+            (from * ((1.0 - fraction) * angle).sin() + to * (fraction * angle).sin()) / angle.sin()
+        };
+        let lat = Angle::new::<radian>(direction.z.clamp(-1.0, 1.0).asin());
+        let lon = Angle::new::<radian>(direction.y.atan2(direction.x));
+        let alt = previous.alt + (next.alt - previous.alt) * fraction;
+        (lat, lon, alt)
+    }
+}
+
+/// Computes the angle in radians between two unit vectors.
+fn central_angle(from: &Vector3<f64>, to: &Vector3<f64>) -> f64 {
+    from.cross(to).norm().atan2(from.dot(to))
+}
@@ -0,0 +1,257 @@
+use nyx_space::{time::Epoch, Orbit};
+use rayon::prelude::*;
+use uom::si::{
+    f64::{Angle, Time},
+    time::millisecond,
+};
+
+use crate::{
+    constellation::node::{Node, NodeId},
+    groundstation::Groundstation,
+    helper,
+    satellite::{propagate_orbit, Satellite},
+};
+
+/// Tolerance of the root-finding for the AOS/LOS epochs.
+const EPOCH_TOLERANCE_MS: f64 = 1.0;
+
+/// Inverse of the golden ratio used by the search for the maximum elevation.
+const INVERSE_GOLDEN_RATIO: f64 = 0.618_033_988_749_895;
+
+/// A contact window between a ground station and a satellite.
+#[derive(Debug, Clone, Copy)]
+pub struct VisibilityWindow {
+    groundstation: NodeId,
+    satellite: NodeId,
+    /// Acquisition of signal (first epoch with sufficient elevation)
+    aos: Epoch,
+    /// Loss of signal (last epoch with sufficient elevation)
+    los: Epoch,
+    max_elevation: Angle,
+    max_elevation_epoch: Epoch,
+}
+
+impl VisibilityWindow {
+    pub fn get_groundstation(&self) -> NodeId {
+        self.groundstation
+    }
+
+    pub fn get_satellite(&self) -> NodeId {
+        self.satellite
+    }
+
+    pub fn get_aos(&self) -> Epoch {
+        self.aos
+    }
+
+    pub fn get_los(&self) -> Epoch {
+        self.los
+    }
+
+    pub fn get_max_elevation(&self) -> Angle {
+        self.max_elevation
+    }
+
+    pub fn get_max_elevation_epoch(&self) -> Epoch {
+        self.max_elevation_epoch
+    }
+}
+
+/// Orbits of a satellite sampled at a fixed step, used to bracket the visibility changes.
+struct SampledOrbit {
+    step: Time,
+    samples: Vec<(Time, Orbit)>,
+}
+
+impl SampledOrbit {
+    fn new(satellite: &Satellite, duration: Time, step: Time) -> Self {
+        let mut samples = vec![(Time::new::<millisecond>(0.0), satellite.get_orbit())];
+        let mut offset = Time::new::<millisecond>(0.0);
+        while offset < duration {
+            let next = if offset + step > duration {
+                duration
+            } else {
+                offset + step
+            };
+            let (_, previous) = samples.last().unwrap();
+            let orbit = propagate_orbit(*previous, helper::duration(next - offset));
+            samples.push((next, orbit));
+            offset = next;
+        }
+        SampledOrbit { step, samples }
+    }
+
+    /// Returns the orbit at the given offset, propagated from the closest preceding sample.
+    fn orbit_at(&self, offset: Time) -> Orbit {
+        let index = (offset / self.step).value.floor() as usize;
+        let (sample_offset, orbit) = self.samples[index.min(self.samples.len() - 1)];
+        propagate_orbit(orbit, helper::duration(offset - sample_offset))
+    }
+}
+
+/// Computes the visibility windows of all ground station/satellite pairs within the interval
+/// that starts at the current epoch of the satellites and lasts for the given duration.
+///
+/// The satellite orbits are sampled with the given step to bracket the rising and setting edges.
+/// Each edge is then refined by bisection to an accuracy of one millisecond.
+/// Windows that start before or end after the interval are truncated at its bounds.
+///
+/// # Important
+///
+/// The step must be shorter than the shortest contact,
+/// since passes which start and end between two samples are not detected.
+pub(crate) fn visibility_windows(
+    groundstations: &[Groundstation],
+    satellites: &[Satellite],
+    duration: Time,
+    step: Time,
+) -> Vec<VisibilityWindow> {
+    assert!(step.get::<millisecond>() > 0.0);
+    assert!(duration.get::<millisecond>() >= 0.0);
+
+    satellites
+        .par_iter()
+        .flat_map_iter(|sat| {
+            let sampled = SampledOrbit::new(sat, duration, step);
+            groundstations
+                .iter()
+                .flat_map(|gs| windows_of_pair(gs, sat.get_id(), &sampled))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Finds all visibility windows of a single ground station/satellite pair.
+fn windows_of_pair(
+    groundstation: &Groundstation,
+    satellite: NodeId,
+    sampled: &SampledOrbit,
+) -> Vec<VisibilityWindow> {
+    let elevation_at = |offset: Time| groundstation.elevation(&sampled.orbit_at(offset));
+    let visible_at = |offset: Time| groundstation.is_above_min_elevation(elevation_at(offset));
+
+    let mut windows = vec![];
+    let mut aos: Option<Time> = None;
+    let mut previous: Option<(Time, bool)> = None;
+    for (offset, orbit) in sampled.samples.iter() {
+        let visible = groundstation.is_above_min_elevation(groundstation.elevation(orbit));
+        match previous {
+            // visible at the start of the interval
+            None if visible => aos = Some(*offset),
+            // rising edge
+            Some((previous_offset, false)) if visible => {
+                aos = Some(bisect(previous_offset, *offset, &visible_at, true));
+            }
+            // setting edge
+            Some((previous_offset, true)) if !visible => {
+                let los = bisect(previous_offset, *offset, &visible_at, false);
+                let aos = aos.take().unwrap();
+                windows.push(create_window(groundstation, satellite, sampled, aos, los));
+            }
+            _ => {}
+        }
+        previous = Some((*offset, visible));
+    }
+    // still visible at the end of the interval
+    if let (Some(aos), Some((los, true))) = (aos, previous) {
+        windows.push(create_window(groundstation, satellite, sampled, aos, los));
+    }
+    windows
+}
+
+/// Finds the offset at which the visibility changes between `lower` and `upper` by bisection.
+/// Returns the offset within the tolerance for which the satellite is visible,
+/// i.e., the upper bound for rising and the lower bound for setting edges.
+fn bisect(
+    mut lower: Time,
+    mut upper: Time,
+    visible_at: &impl Fn(Time) -> bool,
+    rising: bool,
+) -> Time {
+    while (upper - lower).get::<millisecond>() > EPOCH_TOLERANCE_MS {
+        let middle = lower + (upper - lower) / 2.0;
+        if visible_at(middle) == rising {
+            upper = middle;
+        } else {
+            lower = middle;
+        }
+    }
+    if rising {
+        upper
+    } else {
+        lower
+    }
+}
+
+/// Creates the window between the given offsets and searches its maximum elevation.
+fn create_window(
+    groundstation: &Groundstation,
+    satellite: NodeId,
+    sampled: &SampledOrbit,
+    aos: Time,
+    los: Time,
+) -> VisibilityWindow {
+    let elevation_at = |offset: Time| groundstation.elevation(&sampled.orbit_at(offset));
+    let (max_offset, max_elevation) = maximize(aos, los, sampled.step, &elevation_at);
+    let start = sampled.samples[0].1.dt;
+    VisibilityWindow {
+        groundstation: groundstation.get_id(),
+        satellite,
+        aos: start + helper::duration(aos),
+        los: start + helper::duration(los),
+        max_elevation,
+        max_elevation_epoch: start + helper::duration(max_offset),
+    }
+}
+
+/// Searches the maximum elevation between `lower` and `upper`.
+/// The coarse maximum is located with the given step and refined by a golden-section search.
+fn maximize(
+    lower: Time,
+    upper: Time,
+    step: Time,
+    elevation_at: &impl Fn(Time) -> Angle,
+) -> (Time, Angle) {
+    // coarse search over the window, including both edges
+    let mut best = (lower, elevation_at(lower));
+    let mut offset = lower;
+    while offset < upper {
+        offset = if offset + step > upper {
+            upper
+        } else {
+            offset + step
+        };
+        let elevation = elevation_at(offset);
+        if elevation > best.1 {
+            best = (offset, elevation);
+        }
+    }
+
+    // golden-section search around the coarse maximum
+    let mut a = if best.0 - step < lower {
+        lower
+    } else {
+        best.0 - step
+    };
+    let mut b = if best.0 + step > upper {
+        upper
+    } else {
+        best.0 + step
+    };
+    while (b - a).get::<millisecond>() > EPOCH_TOLERANCE_MS {
+        let c = b - (b - a) * INVERSE_GOLDEN_RATIO;
+        let d = a + (b - a) * INVERSE_GOLDEN_RATIO;
+        if elevation_at(c) > elevation_at(d) {
+            b = d;
+        } else {
+            a = c;
+        }
+    }
+    let middle = a + (b - a) / 2.0;
+    let elevation = elevation_at(middle);
+    if elevation > best.1 {
+        (middle, elevation)
+    } else {
+        best
+    }
+}